@@ -0,0 +1,88 @@
+// Code generated by State Machine Builder. DO NOT EDIT.
+
+package mealy
+
+// State is a state of the state machine.
+type State int
+
+const (
+	StateQ1 State = iota
+	StateQ2
+	StateQ3
+)
+
+// String returns the name of the state.
+func (s State) String() string {
+	switch s {
+	case StateQ1:
+		return "q1"
+	case StateQ2:
+		return "q2"
+	case StateQ3:
+		return "q3"
+	}
+	return "unknown"
+}
+
+// Input holds the input symbols of one step.
+type Input struct {
+	I0 bool
+	I1 bool
+}
+
+// Output holds the output symbols of one step.
+type Output struct {
+	O0 bool
+	O1 bool
+}
+
+// Machine is one instance of the state machine.
+type Machine struct {
+	State         State
+	PreviousState State
+	Output        Output
+}
+
+// New returns a machine in the start state.
+func New() *Machine {
+	m := &Machine{}
+	m.Reset()
+	return m
+}
+
+// Reset resets the state, outputs, and variables to their initial values.
+func (m *Machine) Reset() {
+	m.State = StateQ1
+	m.PreviousState = StateQ1
+	m.Output = Output{}
+}
+
+// Step executes one step of the state machine with the given input.
+func (m *Machine) Step(input Input) Output {
+	m.PreviousState = m.State
+	m.Output = Output{}
+
+	switch m.State {
+	case StateQ1:
+		if input.I0 && !input.I1 {
+			m.Output.O0 = true
+			m.State = StateQ2
+		}
+	case StateQ2:
+		if input.I1 {
+			m.Output.O1 = true
+			m.State = StateQ3
+		} else if !input.I0 {
+			m.Output.O0 = true
+			m.State = StateQ1
+		}
+	case StateQ3:
+	}
+
+	return m.Output
+}
+
+// IsEnd returns whether the machine is in an end state.
+func (m *Machine) IsEnd() bool {
+	return m.State == StateQ3
+}
@@ -0,0 +1,50 @@
+// Code generated by State Machine Builder. DO NOT EDIT.
+
+package mealy
+
+import "testing"
+
+// q1 -> q2 with i0
+func TestTransition0(t *testing.T) {
+	m := New()
+	m.State = StateQ1
+
+	output := m.Step(Input{I0: true})
+
+	if m.State != StateQ2 {
+		t.Errorf("state = %v, want %v", m.State, StateQ2)
+	}
+	if want := (Output{O0: true}); output != want {
+		t.Errorf("output = %+v, want %+v", output, want)
+	}
+}
+
+// q2 -> q3 with i1
+func TestTransition1(t *testing.T) {
+	m := New()
+	m.State = StateQ2
+
+	output := m.Step(Input{I1: true})
+
+	if m.State != StateQ3 {
+		t.Errorf("state = %v, want %v", m.State, StateQ3)
+	}
+	if want := (Output{O1: true}); output != want {
+		t.Errorf("output = %+v, want %+v", output, want)
+	}
+}
+
+// q2 -> q1
+func TestTransition2(t *testing.T) {
+	m := New()
+	m.State = StateQ2
+
+	output := m.Step(Input{})
+
+	if m.State != StateQ1 {
+		t.Errorf("state = %v, want %v", m.State, StateQ1)
+	}
+	if want := (Output{O0: true}); output != want {
+		t.Errorf("output = %+v, want %+v", output, want)
+	}
+}
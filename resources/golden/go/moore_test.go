@@ -0,0 +1,35 @@
+// Code generated by State Machine Builder. DO NOT EDIT.
+
+package moore
+
+import "testing"
+
+// q1 -> q2 with i0, i1
+func TestTransition0(t *testing.T) {
+	m := New()
+	m.State = StateQ1
+
+	output := m.Step(Input{I0: true, I1: true})
+
+	if m.State != StateQ2 {
+		t.Errorf("state = %v, want %v", m.State, StateQ2)
+	}
+	if want := (Output{O5: true, O6: true}); output != want {
+		t.Errorf("output = %+v, want %+v", output, want)
+	}
+}
+
+// q2 -> q3 with i1
+func TestTransition1(t *testing.T) {
+	m := New()
+	m.State = StateQ2
+
+	output := m.Step(Input{I1: true})
+
+	if m.State != StateQ3 {
+		t.Errorf("state = %v, want %v", m.State, StateQ3)
+	}
+	if want := (Output{}); output != want {
+		t.Errorf("output = %+v, want %+v", output, want)
+	}
+}
@@ -0,0 +1,94 @@
+// Code generated by State Machine Builder. DO NOT EDIT.
+
+/** The moore machine moore. */
+public final class Moore {
+
+    /** The input symbols of one step. */
+    public static final class Input {
+        public boolean i0;
+        public boolean i1;
+    }
+
+    /** The output symbols of one step. */
+    public static final class Output {
+        public boolean o5;
+        public boolean o6;
+    }
+
+    /** The states. Every state knows its outgoing transitions. */
+    public enum State {
+        q1 {
+            @Override
+            State next(Input input) {
+                if (input.i0 && input.i1) {
+                    return q2;
+                }
+                return this;
+            }
+
+            @Override
+            void output(Input input, Output output) {
+                output.o5 = true;
+                output.o6 = true;
+            }
+        },
+        q2 {
+            @Override
+            State next(Input input) {
+                if (input.i1) {
+                    return q3;
+                }
+                return this;
+            }
+        },
+        q3 {
+            @Override
+            State next(Input input) {
+                return this;
+            }
+        };
+
+        /** Returns the next state for the input. */
+        abstract State next(Input input);
+
+        /** Sets the outputs of this state for the input. */
+        void output(Input input, Output output) {
+        }
+    }
+
+    private State state = State.q1;
+    private State previousState = State.q1;
+    private Output output = new Output();
+
+    /** Executes one step of the state machine with the given input. */
+    public Output step(Input input) {
+        previousState = state;
+        output = new Output();
+        state.output(input, output);
+        state = state.next(input);
+        return output;
+    }
+
+    /** Resets the state, outputs, and variables to their initial values. */
+    public void reset() {
+        state = State.q1;
+        previousState = State.q1;
+        output = new Output();
+    }
+
+    public State state() {
+        return state;
+    }
+
+    public State previousState() {
+        return previousState;
+    }
+
+    public Output output() {
+        return output;
+    }
+
+    public boolean isEndState() {
+        return state == State.q3;
+    }
+}
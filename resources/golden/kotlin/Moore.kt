@@ -0,0 +1,89 @@
+// Code generated by State Machine Builder. DO NOT EDIT.
+
+/** The moore machine moore. */
+class Moore {
+
+    /** The input symbols of one step. */
+    class Input(
+        var i0: Boolean = false,
+        var i1: Boolean = false,
+    )
+
+    /** The output symbols of one step. */
+    class Output(
+        var o5: Boolean = false,
+        var o6: Boolean = false,
+    )
+
+    /** The states. Every state knows its outgoing transitions. */
+    sealed class State {
+        /** Returns the next state for the input. */
+        internal abstract fun next(input: Input): State
+
+        /** Sets the outputs of this state for the input. */
+        internal open fun output(input: Input, output: Output) {}
+
+        object q1 : State() {
+            override fun next(input: Input): State {
+                if (input.i0 && input.i1) {
+                    return q2
+                }
+                return this
+            }
+
+            override fun output(input: Input, output: Output) {
+                output.o5 = true
+                output.o6 = true
+            }
+
+            override fun toString() = "q1"
+        }
+
+        object q2 : State() {
+            override fun next(input: Input): State {
+                if (input.i1) {
+                    return q3
+                }
+                return this
+            }
+
+            override fun toString() = "q2"
+        }
+
+        object q3 : State() {
+            override fun next(input: Input): State {
+                return this
+            }
+
+            override fun toString() = "q3"
+        }
+    }
+
+    var state: State = State.q1
+        private set
+
+    var previousState: State = State.q1
+        private set
+
+    var output: Output = Output()
+        private set
+
+    val isEndState: Boolean
+        get() = state == State.q3
+
+    /** Executes one step of the state machine with the given input. */
+    fun step(input: Input): Output {
+        previousState = state
+        output = Output()
+        state.output(input, output)
+        state = state.next(input)
+        return output
+    }
+
+    /** Resets the state, outputs, and variables to their initial values. */
+    fun reset() {
+        state = State.q1
+        previousState = State.q1
+        output = Output()
+    }
+}
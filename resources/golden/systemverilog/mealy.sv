@@ -0,0 +1,75 @@
+// Code generated by State Machine Builder. DO NOT EDIT.
+
+// Mealy machine mealy with one-hot state encoding.
+// The reset is synchronous and active high.
+module mealy (
+    input  logic clk,
+    input  logic rst,
+    input  logic i0,
+    input  logic i1,
+    output logic o0,
+    output logic o1,
+    output logic is_end
+);
+
+    typedef enum logic [2:0] {
+        S_q1 = 3'b001,
+        S_q2 = 3'b010,
+        S_q3 = 3'b100
+    } state_t;
+
+    state_t state, next_state;
+
+    // State register
+    always_ff @(posedge clk) begin
+        if (rst)
+            state <= S_q1;
+        else
+            state <= next_state;
+    end
+
+    // Next state logic
+    always_comb begin
+        next_state = state;
+        case (state)
+            S_q1: begin
+                if (i0 && !i1)
+                    next_state = S_q2;
+            end
+            S_q2: begin
+                if (i1)
+                    next_state = S_q3;
+                else if (!i0)
+                    next_state = S_q1;
+            end
+            S_q3: begin
+            end
+            default: next_state = S_q1;
+        endcase
+    end
+
+    // Output logic, depends on the state and the inputs
+    always_comb begin
+        o0 = 1'b0;
+        o1 = 1'b0;
+        case (state)
+            S_q1: begin
+                if (i0 && !i1) begin
+                    o0 = 1'b1;
+                end
+            end
+            S_q2: begin
+                if (i1) begin
+                    o1 = 1'b1;
+                end
+                else if (!i0) begin
+                    o0 = 1'b1;
+                end
+            end
+            default: ;
+        endcase
+    end
+
+    assign is_end = (state == S_q3);
+
+endmodule
@@ -0,0 +1,64 @@
+// Code generated by State Machine Builder. DO NOT EDIT.
+
+// Moore machine moore_gray with gray state encoding.
+// The reset is synchronous and active high.
+module moore_gray (
+    input  logic clk,
+    input  logic rst,
+    input  logic i0,
+    input  logic i1,
+    output logic o5,
+    output logic o6,
+    output logic is_end
+);
+
+    typedef enum logic [1:0] {
+        S_q1 = 2'b00,
+        S_q2 = 2'b01,
+        S_q3 = 2'b11
+    } state_t;
+
+    state_t state, next_state;
+
+    // State register
+    always_ff @(posedge clk) begin
+        if (rst)
+            state <= S_q1;
+        else
+            state <= next_state;
+    end
+
+    // Next state logic
+    always_comb begin
+        next_state = state;
+        case (state)
+            S_q1: begin
+                if (i0 && i1)
+                    next_state = S_q2;
+            end
+            S_q2: begin
+                if (i1)
+                    next_state = S_q3;
+            end
+            S_q3: begin
+            end
+            default: next_state = S_q1;
+        endcase
+    end
+
+    // Output logic, depends on the state only
+    always_comb begin
+        o5 = 1'b0;
+        o6 = 1'b0;
+        case (state)
+            S_q1: begin
+                o5 = 1'b1;
+                o6 = 1'b1;
+            end
+            default: ;
+        endcase
+    end
+
+    assign is_end = (state == S_q3);
+
+endmodule
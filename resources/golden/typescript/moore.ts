@@ -0,0 +1,60 @@
+// Code generated by State Machine Builder. DO NOT EDIT.
+
+/** A state of the moore state machine. */
+export type State =
+  | "q1"
+  | "q2"
+  | "q3";
+
+/** All states in the order of the definition. */
+export const states: readonly State[] = ["q1", "q2", "q3"];
+
+/** The start state. */
+export const initialState: State = "q1";
+
+/** The input symbols of one step. */
+export interface Input {
+  i0: boolean;
+  i1: boolean;
+}
+
+/** The output symbols of one step. */
+export interface Output {
+  o5: boolean;
+  o6: boolean;
+}
+
+/** The result of one step: the new state and the outputs. */
+export interface StepResult {
+  state: State;
+  output: Output;
+}
+
+/** Executes one step of the state machine. The function has no side effects. */
+export function step(state: State, input: Input): StepResult {
+  const output: Output = { o5: false, o6: false };
+
+  switch (state) {
+    case "q1":
+      output.o5 = true;
+      output.o6 = true;
+      if (input.i0 && input.i1) {
+        return { state: "q2", output };
+      }
+      break;
+    case "q2":
+      if (input.i1) {
+        return { state: "q3", output };
+      }
+      break;
+    case "q3":
+      break;
+  }
+
+  return { state, output };
+}
+
+/** Returns whether the state is an end state. */
+export function isEndState(state: State): boolean {
+  return state === "q3";
+}
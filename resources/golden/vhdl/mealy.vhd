@@ -0,0 +1,84 @@
+-- Code generated by State Machine Builder. DO NOT EDIT.
+
+library ieee;
+use ieee.std_logic_1164.all;
+
+-- Mealy machine mealy.
+-- The reset is synchronous and active high.
+entity mealy is
+    port (
+        clk : in std_logic;
+        rst : in std_logic;
+        i0 : in std_logic;
+        i1 : in std_logic;
+        o0 : out std_logic;
+        o1 : out std_logic;
+        is_end : out std_logic
+    );
+end entity mealy;
+
+architecture rtl of mealy is
+
+    type state_t is (S_q1, S_q2, S_q3);
+
+    signal state : state_t := S_q1;
+    signal next_state : state_t;
+
+begin
+
+    -- State register
+    state_register : process (clk)
+    begin
+        if rising_edge(clk) then
+            if rst = '1' then
+                state <= S_q1;
+            else
+                state <= next_state;
+            end if;
+        end if;
+    end process state_register;
+
+    -- Next state logic
+    next_state_logic : process (state, i0, i1)
+    begin
+        next_state <= state;
+        case state is
+            when S_q1 =>
+                if i0 = '1' and i1 = '0' then
+                    next_state <= S_q2;
+                end if;
+            when S_q2 =>
+                if i1 = '1' then
+                    next_state <= S_q3;
+                elsif i0 = '0' then
+                    next_state <= S_q1;
+                end if;
+            when S_q3 =>
+                null;
+        end case;
+    end process next_state_logic;
+
+    -- Output logic, depends on the state and the inputs
+    output_logic : process (state, i0, i1)
+    begin
+        o0 <= '0';
+        o1 <= '0';
+        case state is
+            when S_q1 =>
+                if i0 = '1' and i1 = '0' then
+                    o0 <= '1';
+                end if;
+            when S_q2 =>
+                if i1 = '1' then
+                    o1 <= '1';
+                elsif i0 = '0' then
+                    o0 <= '1';
+                end if;
+            when S_q3 =>
+                null;
+        end case;
+    end process output_logic;
+
+    is_end <= '1' when state = S_q3 else '0';
+
+end architecture rtl;
@@ -0,0 +1,77 @@
+-- Code generated by State Machine Builder. DO NOT EDIT.
+
+library ieee;
+use ieee.std_logic_1164.all;
+
+-- Moore machine moore.
+-- The reset is synchronous and active high.
+entity moore is
+    port (
+        clk : in std_logic;
+        rst : in std_logic;
+        i0 : in std_logic;
+        i1 : in std_logic;
+        o5 : out std_logic;
+        o6 : out std_logic;
+        is_end : out std_logic
+    );
+end entity moore;
+
+architecture rtl of moore is
+
+    type state_t is (S_q1, S_q2, S_q3);
+
+    signal state : state_t := S_q1;
+    signal next_state : state_t;
+
+begin
+
+    -- State register
+    state_register : process (clk)
+    begin
+        if rising_edge(clk) then
+            if rst = '1' then
+                state <= S_q1;
+            else
+                state <= next_state;
+            end if;
+        end if;
+    end process state_register;
+
+    -- Next state logic
+    next_state_logic : process (state, i0, i1)
+    begin
+        next_state <= state;
+        case state is
+            when S_q1 =>
+                if i0 = '1' and i1 = '1' then
+                    next_state <= S_q2;
+                end if;
+            when S_q2 =>
+                if i1 = '1' then
+                    next_state <= S_q3;
+                end if;
+            when S_q3 =>
+                null;
+        end case;
+    end process next_state_logic;
+
+    -- Output logic, depends on the state only
+    output_logic : process (state)
+    begin
+        o5 <= '0';
+        o6 <= '0';
+        case state is
+            when S_q1 =>
+                o5 <= '1';
+                o6 <= '1';
+            when S_q2 =>
+                null;
+            when S_q3 =>
+                null;
+        end case;
+    end process output_logic;
+
+    is_end <= '1' when state = S_q3 else '0';
+
+end architecture rtl;
@@ -1,4 +1,4 @@
-    case /*CODE:CASE_NAME*/:
-    /*CODE:CASE_CODE*/
-    break;
-    
\ No newline at end of file
+    case {{ name }}_{{ state.name }}:
+{% include kind ~ "/case_code.c" %}
+        break;
+
@@ -0,0 +1 @@
+{% for term in transition.guard %}{% if term.negated %}!{% endif %}input.{{ term.symbol }}{% if not loop.last %} && {% endif %}{% else %}true{% endfor %}
\ No newline at end of file
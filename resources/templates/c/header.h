@@ -1,26 +1,65 @@
-#pragma once
+#ifndef SM_{{ name | upper_snake_case }}_H
+#define SM_{{ name | upper_snake_case }}_H
 
 #include <stdbool.h>
+{% if variables %}
+#include <stdint.h>
+{% endif %}
 
-enum SMStates_/*CODE:NAME*/
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+enum SMStates_{{ name }}
+{
+{% for state in states %}
+    {{ name }}_{{ state.name }},
+{% endfor %}
+};
+
+struct SMInput_{{ name }}
 {
-/*CODE:STATES_ENUM*/
+{% for input in inputs %}
+    bool {{ input }};
+{% else %}
+    bool unused;
+{% endfor %}
 };
 
-struct SMInput_/*CODE:NAME*/
+struct SMOutput_{{ name }}
 {
-/*CODE:INPUTS_DECLARATION*/
+{% for output in outputs %}
+    bool {{ output }};
+{% else %}
+    bool unused;
+{% endfor %}
 };
 
-struct SMOutput_/*CODE:NAME*/
+{% set c_types = {"bool": "bool", "int": "int32_t", "uint": "uint32_t", "float": "float"} %}
+/* The context of one instance of the state machine. */
+struct SM_{{ name }}
 {
-/*CODE:OUTPUTS_DECLARATION*/
+    enum SMStates_{{ name }} state;
+    enum SMStates_{{ name }} previous_state;
+    struct SMOutput_{{ name }} output;
+{% for variable in variables %}
+    {{ c_types[variable.type] }} {{ variable.name }};
+{% endfor %}
 };
 
-enum SMStates_/*CODE:NAME*/ state_/*CODE:NAME*/ = /*CODE:INITIAL_STATE*/;
+/* Initializes the context. Must be called before the first step. */
+void sm_{{ name }}_init(struct SM_{{ name }} *ctx);
+
+/* Executes one step of the state machine with the given input. */
+void sm_{{ name }}_step(struct SM_{{ name }} *ctx, struct SMInput_{{ name }} input);
+
+/* Resets the state, outputs, and variables to their initial values. */
+void sm_{{ name }}_reset(struct SM_{{ name }} *ctx);
+
+bool sm_{{ name }}_is_end_state(const struct SM_{{ name }} *ctx);
 
-void sm_/*CODE:NAME*/ ( enum sm_states_/*CODE:NAME*/ *state,
-    struct SMInput_/*CODE:NAME*/ input,
-    struct SMOutput_/*CODE:NAME*/ *output);
+#ifdef __cplusplus
+}
+#endif
 
-bool sm_/*CODE:NAME*/_is_end_state(enum sm_states_/*CODE:NAME*/ state);
+#endif /* SM_{{ name | upper_snake_case }}_H */
@@ -1 +1,3 @@
-/*CODE:CASE_IFS*/
\ No newline at end of file
+{% for transition in state.transitions %}
+{% include "mealy/case_if.c" %}
+{% endfor %}
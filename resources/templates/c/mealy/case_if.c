@@ -1,5 +1,7 @@
-if (/*CODE:IF*/)
-{
-/*CODE:SET_OUTPUT*/
-/*CODE:SET_NEW_STATE*/
-}
\ No newline at end of file
+        {{ "else " if not loop.first }}if ({% include "guard.c" %})
+        {
+{% for output in transition.outputs %}
+            ctx->output.{{ output }} = true;
+{% endfor %}
+            ctx->state = {{ name }}_{{ transition.to }};
+        }
@@ -1,2 +1,6 @@
-    /*CODE:SET_OUTPUT*/
-    /*CODE:CASE_IFS*/
\ No newline at end of file
+{% for output in state.outputs %}
+        ctx->output.{{ output }} = true;
+{% endfor %}
+{% for transition in state.transitions %}
+{% include "moore/case_if.c" %}
+{% endfor %}
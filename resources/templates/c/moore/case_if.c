@@ -1,4 +1,4 @@
-    if (/*CODE:IF*/)
+        {{ "else " if not loop.first }}if ({% include "guard.c" %})
         {
-            /*CODE:SET_NEW_STATE*/
-        }
\ No newline at end of file
+            ctx->state = {{ name }}_{{ transition.to }};
+        }
@@ -1,18 +1,48 @@
-#include "/*CODE:NAME*/.h"
+#include "{{ name }}.h"
 
-void sm_/*CODE:NAME*/ (enum SMStates_/*CODE:NAME*/ *state,
-    struct SMInput_/*CODE:NAME*/ input,
-    struct SMOutput_/*CODE:NAME*/ *output)
+#include <string.h>
+
+void sm_{{ name }}_init(struct SM_{{ name }} *ctx)
+{
+    memset(ctx, 0, sizeof(*ctx));
+    sm_{{ name }}_reset(ctx);
+}
+
+void sm_{{ name }}_step(struct SM_{{ name }} *ctx, struct SMInput_{{ name }} input)
 {
-/*CODE:RESET_OUTPUT*/
-    switch (*state)
+    (void)input;
+
+    ctx->previous_state = ctx->state;
+
+{% for output in outputs %}
+    ctx->output.{{ output }} = false;
+{% endfor %}
+
+    switch (ctx->state)
     {
-/*CODE:CASE*/
+{% for state in states %}
+{% include "case.c" %}
+{% endfor %}
     default:
         break;
     }
 }
 
-bool sm_/*CODE:NAME*/_is_end_state(enum sm_states_/*CODE:NAME*/ state){
-    return /*CODE:END_STATE*/;
+void sm_{{ name }}_reset(struct SM_{{ name }} *ctx)
+{
+    ctx->state = {{ name }}_{{ start_state }};
+    ctx->previous_state = {{ name }}_{{ start_state }};
+{% for output in outputs %}
+    ctx->output.{{ output }} = false;
+{% endfor %}
+{% for variable in variables %}
+    ctx->{{ variable.name }} = {{ variable.initial }};
+{% endfor %}
+}
+
+bool sm_{{ name }}_is_end_state(const struct SM_{{ name }} *ctx)
+{
+    (void)ctx;
+
+    return {% for end_state in end_states %}(ctx->state == {{ name }}_{{ end_state }}){% if not loop.last %} || {% endif %}{% else %}false{% endfor %};
 }
@@ -0,0 +1,119 @@
+{% set class_name = name | pascal_case %}
+{% set cpp_types = {"bool": "bool", "int": "std::int32_t", "uint": "std::uint32_t", "float": "float"} %}
+#ifndef SM_{{ name | upper_snake_case }}_HPP
+#define SM_{{ name | upper_snake_case }}_HPP
+
+#include <cstdint>
+
+class {{ class_name }}
+{
+public:
+    enum class State
+    {
+{% for state in states %}
+        {{ state.name }},
+{% endfor %}
+    };
+
+    struct Input
+    {
+{% for input in inputs %}
+        bool {{ input }} = false;
+{% endfor %}
+    };
+
+    struct Output
+    {
+{% for output in outputs %}
+        bool {{ output }} = false;
+{% endfor %}
+    };
+
+{% for variable in variables %}
+    {{ cpp_types[variable.type] }} {{ variable.name }} = {{ variable.initial }};
+{% endfor %}
+{% if variables %}
+
+{% endif %}
+{% if hooks %}
+    virtual ~{{ class_name }}() = default;
+
+{% endif %}
+    /// Executes one step of the state machine with the given input.
+    const Output &step([[maybe_unused]] const Input &input)
+    {
+        previousState_ = state_;
+        output_ = Output{};
+
+        switch (state_)
+        {
+{% for state in states %}
+        case State::{{ state.name }}:
+{% for output in state.outputs %}
+            output_.{{ output }} = true;
+{% endfor %}
+{% for transition in state.transitions %}
+            {{ "else " if not loop.first }}if ({% for term in transition.guard %}{% if term.negated %}!{% endif %}input.{{ term.symbol }}{% if not loop.last %} && {% endif %}{% else %}true{% endfor %})
+            {
+{% for output in transition.outputs %}
+                output_.{{ output }} = true;
+{% endfor %}
+                transition(State::{{ transition.to }});
+            }
+{% endfor %}
+            break;
+{% endfor %}
+        }
+
+        return output_;
+    }
+
+    /// Resets the state, outputs, and variables to their initial values.
+    void reset()
+    {
+        state_ = State::{{ start_state }};
+        previousState_ = State::{{ start_state }};
+        output_ = Output{};
+{% for variable in variables %}
+        {{ variable.name }} = {{ variable.initial }};
+{% endfor %}
+    }
+
+    State state() const { return state_; }
+
+    State previousState() const { return previousState_; }
+
+    const Output &output() const { return output_; }
+
+    bool isEndState() const
+    {
+        return {% for end_state in end_states %}state_ == State::{{ end_state }}{% if not loop.last %} || {% endif %}{% else %}false{% endfor %};
+    }
+{% if hooks %}
+
+protected:
+    /// Called after a transition entered the state.
+    virtual void onEntry([[maybe_unused]] State state) {}
+
+    /// Called before a transition leaves the state.
+    virtual void onExit([[maybe_unused]] State state) {}
+{% endif %}
+
+private:
+    void transition(State next)
+    {
+{% if hooks %}
+        onExit(state_);
+        state_ = next;
+        onEntry(state_);
+{% else %}
+        state_ = next;
+{% endif %}
+    }
+
+    State state_ = State::{{ start_state }};
+    State previousState_ = State::{{ start_state }};
+    Output output_{};
+};
+
+#endif // SM_{{ name | upper_snake_case }}_HPP
@@ -0,0 +1,102 @@
+{% set go_types = {"bool": "bool", "int": "int32", "uint": "uint32", "float": "float32"} %}
+{% set state_prefix = "State" %}
+// Code generated by State Machine Builder. DO NOT EDIT.
+
+package {{ name | snake_case | replace("_", "") }}
+
+// State is a state of the state machine.
+type State int
+
+const (
+{% for state in states %}
+	{{ state_prefix }}{{ state.name | pascal_case }}{{ " State = iota" if loop.first }}
+{% endfor %}
+)
+
+// String returns the name of the state.
+func (s State) String() string {
+	switch s {
+{% for state in states %}
+	case {{ state_prefix }}{{ state.name | pascal_case }}:
+		return "{{ state.name }}"
+{% endfor %}
+	}
+	return "unknown"
+}
+
+// Input holds the input symbols of one step.
+type Input struct {
+{% set width = inputs | map("pascal_case") | map("length") | max %}
+{% for input in inputs %}
+	{{ input | pascal_case | ljust(width) }} bool
+{% endfor %}
+}
+
+// Output holds the output symbols of one step.
+type Output struct {
+{% set width = outputs | map("pascal_case") | map("length") | max %}
+{% for output in outputs %}
+	{{ output | pascal_case | ljust(width) }} bool
+{% endfor %}
+}
+
+// Machine is one instance of the state machine.
+type Machine struct {
+{% set width = (["PreviousState"] + variables | map(attribute="name") | map("pascal_case") | list) | map("length") | max %}
+	{{ "State" | ljust(width) }} State
+	{{ "PreviousState" | ljust(width) }} State
+	{{ "Output" | ljust(width) }} Output
+{% for variable in variables %}
+	{{ variable.name | pascal_case | ljust(width) }} {{ go_types[variable.type] }}
+{% endfor %}
+}
+
+// New returns a machine in the start state.
+func New() *Machine {
+	m := &Machine{}
+	m.Reset()
+	return m
+}
+
+// Reset resets the state, outputs, and variables to their initial values.
+func (m *Machine) Reset() {
+	m.State = {{ state_prefix }}{{ start_state | pascal_case }}
+	m.PreviousState = {{ state_prefix }}{{ start_state | pascal_case }}
+	m.Output = Output{}
+{% for variable in variables %}
+	m.{{ variable.name | pascal_case }} = {{ variable.initial }}
+{% endfor %}
+}
+
+// Step executes one step of the state machine with the given input.
+func (m *Machine) Step(input Input) Output {
+	m.PreviousState = m.State
+	m.Output = Output{}
+
+	switch m.State {
+{% for state in states %}
+	case {{ state_prefix }}{{ state.name | pascal_case }}:
+{% for output in state.outputs %}
+		m.Output.{{ output | pascal_case }} = true
+{% endfor %}
+{% for transition in state.transitions %}
+		{{ "} else " if not loop.first }}if {% for term in transition.guard %}{% if term.negated %}!{% endif %}input.{{ term.symbol | pascal_case }}{% if not loop.last %} && {% endif %}{% else %}true{% endfor %} {
+{% for output in transition.outputs %}
+			m.Output.{{ output | pascal_case }} = true
+{% endfor %}
+			m.State = {{ state_prefix }}{{ transition.to | pascal_case }}
+{% if loop.last %}
+		}
+{% endif %}
+{% endfor %}
+{% endfor %}
+	}
+
+	return m.Output
+}
+
+// IsEnd returns whether the machine is in an end state.
+func (m *Machine) IsEnd() bool {
+	return {% for end_state in end_states %}m.State == {{ state_prefix }}{{ end_state | pascal_case }}{% if not loop.last %} || {% endif %}{% else %}false{% endfor %}
+
+}
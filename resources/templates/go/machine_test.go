@@ -0,0 +1,25 @@
+// Code generated by State Machine Builder. DO NOT EDIT.
+
+package {{ name | snake_case | replace("_", "") }}
+
+{% if tests %}
+import "testing"
+{% endif %}
+{% for test in tests %}
+
+// {{ test.from }} -> {{ test.to }}{% if test.inputs %} with {{ test.inputs | join(", ") }}{% endif %}
+
+func TestTransition{{ loop.index0 }}(t *testing.T) {
+	m := New()
+	m.State = State{{ test.from | pascal_case }}
+
+	output := m.Step(Input{ {%- for input in test.inputs %}{{ input | pascal_case }}: true{% if not loop.last %}, {% endif %}{% endfor %}})
+
+	if m.State != State{{ test.to | pascal_case }} {
+		t.Errorf("state = %v, want %v", m.State, State{{ test.to | pascal_case }})
+	}
+	if want := (Output{ {%- for output in test.outputs %}{{ output | pascal_case }}: true{% if not loop.last %}, {% endif %}{% endfor %}}); output != want {
+		t.Errorf("output = %+v, want %+v", output, want)
+	}
+}
+{% endfor %}
@@ -0,0 +1,109 @@
+{% set class_name = name | pascal_case %}
+{% set java_types = {"bool": "boolean", "int": "int", "uint": "long", "float": "double"} %}
+{% macro guard(transition) %}{% for term in transition.guard %}{% if term.negated %}!{% endif %}input.{{ term.symbol }}{% if not loop.last %} && {% endif %}{% else %}true{% endfor %}{% endmacro %}
+// Code generated by State Machine Builder. DO NOT EDIT.
+{% if package %}
+
+package {{ package }};
+{% endif %}
+
+/** The {{ kind }} machine {{ name }}. */
+public final class {{ class_name }} {
+
+    /** The input symbols of one step. */
+    public static final class Input {
+{% for input in inputs %}
+        public boolean {{ input }};
+{% endfor %}
+    }
+
+    /** The output symbols of one step. */
+    public static final class Output {
+{% for output in outputs %}
+        public boolean {{ output }};
+{% endfor %}
+    }
+
+    /** The states. Every state knows its outgoing transitions. */
+    public enum State {
+{% for state in states %}
+        {{ state.name }} {
+            @Override
+            State next(Input input) {
+{% for transition in state.transitions %}
+                if ({{ guard(transition) }}) {
+                    return {{ transition.to }};
+                }
+{% endfor %}
+                return this;
+            }
+{% if state.outputs or (kind == "mealy" and state.transitions) %}
+
+            @Override
+            void output(Input input, Output output) {
+{% for output in state.outputs %}
+                output.{{ output }} = true;
+{% endfor %}
+{% for transition in state.transitions if kind == "mealy" %}
+                {{ "else " if not loop.first }}if ({{ guard(transition) }}) {
+{% for output in transition.outputs %}
+                    output.{{ output }} = true;
+{% endfor %}
+                }
+{% endfor %}
+            }
+{% endif %}
+        }{{ "," if not loop.last else ";" }}
+{% endfor %}
+
+        /** Returns the next state for the input. */
+        abstract State next(Input input);
+
+        /** Sets the outputs of this state for the input. */
+        void output(Input input, Output output) {
+        }
+    }
+{% for variable in variables %}
+
+    public {{ java_types[variable.type] }} {{ variable.name }} = {{ variable.initial }};
+{% endfor %}
+
+    private State state = State.{{ start_state }};
+    private State previousState = State.{{ start_state }};
+    private Output output = new Output();
+
+    /** Executes one step of the state machine with the given input. */
+    public Output step(Input input) {
+        previousState = state;
+        output = new Output();
+        state.output(input, output);
+        state = state.next(input);
+        return output;
+    }
+
+    /** Resets the state, outputs, and variables to their initial values. */
+    public void reset() {
+        state = State.{{ start_state }};
+        previousState = State.{{ start_state }};
+        output = new Output();
+{% for variable in variables %}
+        {{ variable.name }} = {{ variable.initial }};
+{% endfor %}
+    }
+
+    public State state() {
+        return state;
+    }
+
+    public State previousState() {
+        return previousState;
+    }
+
+    public Output output() {
+        return output;
+    }
+
+    public boolean isEndState() {
+        return {% for end_state in end_states %}state == State.{{ end_state }}{% if not loop.last %} || {% endif %}{% else %}false{% endfor %};
+    }
+}
@@ -0,0 +1,101 @@
+{% set class_name = name | pascal_case %}
+{% set kotlin_types = {"bool": "Boolean", "int": "Int", "uint": "Long", "float": "Double"} %}
+{% macro guard(transition) %}{% for term in transition.guard %}{% if term.negated %}!{% endif %}input.{{ term.symbol }}{% if not loop.last %} && {% endif %}{% else %}true{% endfor %}{% endmacro %}
+// Code generated by State Machine Builder. DO NOT EDIT.
+{% if package %}
+
+package {{ package }}
+{% endif %}
+
+/** The {{ kind }} machine {{ name }}. */
+class {{ class_name }} {
+
+    /** The input symbols of one step. */
+    class Input(
+{% for input in inputs %}
+        var {{ input }}: Boolean = false,
+{% endfor %}
+    )
+
+    /** The output symbols of one step. */
+    class Output(
+{% for output in outputs %}
+        var {{ output }}: Boolean = false,
+{% endfor %}
+    )
+
+    /** The states. Every state knows its outgoing transitions. */
+    sealed class State {
+        /** Returns the next state for the input. */
+        internal abstract fun next(input: Input): State
+
+        /** Sets the outputs of this state for the input. */
+        internal open fun output(input: Input, output: Output) {}
+{% for state in states %}
+
+        object {{ state.name }} : State() {
+            override fun next(input: Input): State {
+{% for transition in state.transitions %}
+                if ({{ guard(transition) }}) {
+                    return {{ transition.to }}
+                }
+{% endfor %}
+                return this
+            }
+{% if state.outputs or (kind == "mealy" and state.transitions) %}
+
+            override fun output(input: Input, output: Output) {
+{% for output in state.outputs %}
+                output.{{ output }} = true
+{% endfor %}
+{% for transition in state.transitions if kind == "mealy" %}
+                {{ "else " if not loop.first }}if ({{ guard(transition) }}) {
+{% for output in transition.outputs %}
+                    output.{{ output }} = true
+{% endfor %}
+                }
+{% endfor %}
+            }
+{% endif %}
+
+            override fun toString() = "{{ state.name }}"
+        }
+{% endfor %}
+    }
+{% for variable in variables %}
+
+    var {{ variable.name }}: {{ kotlin_types[variable.type] }} = {{ variable.initial }}
+{% endfor %}
+
+    var state: State = State.{{ start_state }}
+        private set
+
+    var previousState: State = State.{{ start_state }}
+        private set
+
+    var output: Output = Output()
+        private set
+
+    val isEndState: Boolean
+        get() = {% for end_state in end_states %}state == State.{{ end_state }}{% if not loop.last %} || {% endif %}{% else %}false{% endfor %}
+
+
+    /** Executes one step of the state machine with the given input. */
+    fun step(input: Input): Output {
+        previousState = state
+        output = Output()
+        state.output(input, output)
+        state = state.next(input)
+        return output
+    }
+
+    /** Resets the state, outputs, and variables to their initial values. */
+    fun reset() {
+        state = State.{{ start_state }}
+        previousState = State.{{ start_state }}
+        output = Output()
+{% for variable in variables %}
+        {{ variable.name }} = {{ variable.initial }}
+{% endfor %}
+    }
+}
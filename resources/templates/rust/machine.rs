@@ -0,0 +1,142 @@
+{% set rust_types = {"bool": "bool", "int": "i32", "uint": "u32", "float": "f32"} %}
+// Code generated by State Machine Builder. DO NOT EDIT.
+
+/// The {{ kind | title }} machine {{ name }}.
+#[allow(dead_code)]
+pub mod {{ name | snake_case }} {
+    /// A state of the state machine.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum State {
+{% for state in states %}
+        {{ state.name | pascal_case }},
+{% endfor %}
+    }
+
+    impl State {
+        /// All states in the order of the definition.
+        pub const ALL: [State; {{ states | length }}] = [
+{% for state in states %}
+            State::{{ state.name | pascal_case }},
+{% endfor %}
+        ];
+
+        /// Returns the name of the state in the definition.
+        pub fn name(self) -> &'static str {
+            match self {
+{% for state in states %}
+                State::{{ state.name | pascal_case }} => "{{ state.name }}",
+{% endfor %}
+            }
+        }
+    }
+
+    /// The input symbols of one step.
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    pub struct Input {
+{% for input in inputs %}
+        pub {{ input | snake_case }}: bool,
+{% endfor %}
+    }
+
+    /// The output symbols of one step.
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    pub struct Output {
+{% for output in outputs %}
+        pub {{ output | snake_case }}: bool,
+{% endfor %}
+    }
+
+    /// One instance of the state machine.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Machine {
+        state: State,
+        previous_state: State,
+        output: Output,
+{% for variable in variables %}
+        pub {{ variable.name | snake_case }}: {{ rust_types[variable.type] }},
+{% endfor %}
+    }
+
+    impl Default for Machine {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl Machine {
+        /// The state the machine starts in.
+        pub const START_STATE: State = State::{{ start_state | pascal_case }};
+
+        /// Returns a machine in the start state.
+        pub fn new() -> Self {
+            Machine {
+                state: Self::START_STATE,
+                previous_state: Self::START_STATE,
+                output: Output::default(),
+{% for variable in variables %}
+                {{ variable.name | snake_case }}: {{ variable.initial }},
+{% endfor %}
+            }
+        }
+
+        /// Resets the state, outputs, and variables to their initial values.
+        pub fn reset(&mut self) {
+            *self = Self::new();
+        }
+
+        /// Executes one step of the state machine with the given input.
+        pub fn step(&mut self, input: Input) -> Output {
+            let _ = input;
+            self.previous_state = self.state;
+            let mut output = Output::default();
+
+            match self.state {
+{% for state in states %}
+{% if state.outputs or state.transitions %}
+                State::{{ state.name | pascal_case }} => {
+{% for output in state.outputs %}
+                    output.{{ output | snake_case }} = true;
+{% endfor %}
+{% for transition in state.transitions %}
+                    {{ "} else " if not loop.first }}if {% for term in transition.guard %}{% if term.negated %}!{% endif %}input.{{ term.symbol | snake_case }}{% if not loop.last %} && {% endif %}{% else %}true{% endfor %} {
+{% for output in transition.outputs %}
+                        output.{{ output | snake_case }} = true;
+{% endfor %}
+                        self.state = State::{{ transition.to | pascal_case }};
+{% if loop.last %}
+                    }
+{% endif %}
+{% endfor %}
+                }
+{% else %}
+                State::{{ state.name | pascal_case }} => {}
+{% endif %}
+{% endfor %}
+            }
+
+            self.output = output;
+            output
+        }
+
+        /// Returns the current state.
+        pub fn state(&self) -> State {
+            self.state
+        }
+
+        /// Returns the state before the last step.
+        pub fn previous_state(&self) -> State {
+            self.previous_state
+        }
+
+        /// Returns the outputs of the last step.
+        pub fn output(&self) -> Output {
+            self.output
+        }
+
+        /// Returns whether the machine is in an end state.
+        pub fn is_end_state(&self) -> bool {
+            {% if end_states %}matches!(self.state, {% for end_state in end_states %}State::{{ end_state | pascal_case }}{{ " | " if not loop.last }}{% endfor %}){% else %}false{% endif %}
+
+        }
+    }
+}
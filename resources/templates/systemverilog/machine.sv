@@ -0,0 +1,94 @@
+{% macro guard(transition) %}{% for term in transition.guard %}{% if term.negated %}!{% endif %}{{ term.symbol }}{% if not loop.last %} && {% endif %}{% else %}1'b1{% endfor %}{% endmacro %}
+// Code generated by State Machine Builder. DO NOT EDIT.
+
+// {{ kind | title }} machine {{ name }} with {{ encoding }} state encoding.
+// The reset is synchronous and active high.
+module {{ name }} (
+    input  logic clk,
+    input  logic rst,
+{% for input in inputs %}
+    input  logic {{ input }},
+{% endfor %}
+{% for output in outputs %}
+    output logic {{ output }},
+{% endfor %}
+    output logic is_end
+);
+
+    typedef enum logic [{{ state_width - 1 }}:0] {
+{% for state in states %}
+        S_{{ state.name }} = {{ state_codes[state.name] }}{{ "," if not loop.last }}
+{% endfor %}
+    } state_t;
+
+    state_t state, next_state;
+
+    // State register
+    always_ff @(posedge clk) begin
+        if (rst)
+            state <= S_{{ start_state }};
+        else
+            state <= next_state;
+    end
+
+    // Next state logic
+    always_comb begin
+        next_state = state;
+        case (state)
+{% for state in states %}
+            S_{{ state.name }}: begin
+{% for transition in state.transitions %}
+                {{ "else " if not loop.first }}if ({{ guard(transition) }})
+                    next_state = S_{{ transition.to }};
+{% endfor %}
+            end
+{% endfor %}
+            default: next_state = S_{{ start_state }};
+        endcase
+    end
+{% if outputs %}
+
+{% if kind == "moore" %}
+    // Output logic, depends on the state only
+    always_comb begin
+{% for output in outputs %}
+        {{ output }} = 1'b0;
+{% endfor %}
+        case (state)
+{% for state in states if state.outputs %}
+            S_{{ state.name }}: begin
+{% for output in state.outputs %}
+                {{ output }} = 1'b1;
+{% endfor %}
+            end
+{% endfor %}
+            default: ;
+        endcase
+    end
+{% else %}
+    // Output logic, depends on the state and the inputs
+    always_comb begin
+{% for output in outputs %}
+        {{ output }} = 1'b0;
+{% endfor %}
+        case (state)
+{% for state in states if state.transitions %}
+            S_{{ state.name }}: begin
+{% for transition in state.transitions %}
+                {{ "else " if not loop.first }}if ({{ guard(transition) }}) begin
+{% for output in transition.outputs %}
+                    {{ output }} = 1'b1;
+{% endfor %}
+                end
+{% endfor %}
+            end
+{% endfor %}
+            default: ;
+        endcase
+    end
+{% endif %}
+{% endif %}
+
+    assign is_end = {% for end_state in end_states %}(state == S_{{ end_state }}){% if not loop.last %} || {% endif %}{% else %}1'b0{% endfor %};
+
+endmodule
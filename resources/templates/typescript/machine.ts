@@ -0,0 +1,63 @@
+// Code generated by State Machine Builder. DO NOT EDIT.
+
+/** A state of the {{ name }} state machine. */
+export type State =
+{% for state in states %}
+  | "{{ state.name }}"{{ ";" if loop.last }}
+{% endfor %}
+
+/** All states in the order of the definition. */
+export const states: readonly State[] = [{% for state in states %}"{{ state.name }}"{% if not loop.last %}, {% endif %}{% endfor %}];
+
+/** The start state. */
+export const initialState: State = "{{ start_state }}";
+
+/** The input symbols of one step. */
+export interface Input {
+{% for input in inputs %}
+  {{ input }}: boolean;
+{% endfor %}
+}
+
+/** The output symbols of one step. */
+export interface Output {
+{% for output in outputs %}
+  {{ output }}: boolean;
+{% endfor %}
+}
+
+/** The result of one step: the new state and the outputs. */
+export interface StepResult {
+  state: State;
+  output: Output;
+}
+
+/** Executes one step of the state machine. The function has no side effects. */
+export function step(state: State, input: Input): StepResult {
+  const output: Output = { {%- for output in outputs %} {{ output }}: false{{ "," if not loop.last else " " }}{% endfor %}};
+
+  switch (state) {
+{% for state in states %}
+    case "{{ state.name }}":
+{% for output in state.outputs %}
+      output.{{ output }} = true;
+{% endfor %}
+{% for transition in state.transitions %}
+      if ({% for term in transition.guard %}{% if term.negated %}!{% endif %}input.{{ term.symbol }}{% if not loop.last %} && {% endif %}{% else %}true{% endfor %}) {
+{% for output in transition.outputs %}
+        output.{{ output }} = true;
+{% endfor %}
+        return { state: "{{ transition.to }}", output };
+      }
+{% endfor %}
+      break;
+{% endfor %}
+  }
+
+  return { state, output };
+}
+
+/** Returns whether the state is an end state. */
+export function isEndState(state: State): boolean {
+  return {% for end_state in end_states %}state === "{{ end_state }}"{% if not loop.last %} || {% endif %}{% else %}false{% endfor %};
+}
@@ -0,0 +1,113 @@
+{% macro guard(transition) %}{% for term in transition.guard %}{{ term.symbol }} = '{{ "0" if term.negated else "1" }}'{% if not loop.last %} and {% endif %}{% else %}true{% endfor %}{% endmacro %}
+{% set sensitivity = (["state"] + inputs) | join(", ") %}
+-- Code generated by State Machine Builder. DO NOT EDIT.
+
+library ieee;
+use ieee.std_logic_1164.all;
+
+-- {{ kind | title }} machine {{ name }}.
+-- The reset is synchronous and active high.
+entity {{ name }} is
+    port (
+        clk : in std_logic;
+        rst : in std_logic;
+{% for input in inputs %}
+        {{ input }} : in std_logic;
+{% endfor %}
+{% for output in outputs %}
+        {{ output }} : out std_logic;
+{% endfor %}
+        is_end : out std_logic
+    );
+end entity {{ name }};
+
+architecture rtl of {{ name }} is
+
+    type state_t is ({% for state in states %}S_{{ state.name }}{{ ", " if not loop.last }}{% endfor %});
+
+    signal state : state_t := S_{{ start_state }};
+    signal next_state : state_t;
+
+begin
+
+    -- State register
+    state_register : process (clk)
+    begin
+        if rising_edge(clk) then
+            if rst = '1' then
+                state <= S_{{ start_state }};
+            else
+                state <= next_state;
+            end if;
+        end if;
+    end process state_register;
+
+    -- Next state logic
+    next_state_logic : process ({{ sensitivity }})
+    begin
+        next_state <= state;
+        case state is
+{% for state in states %}
+            when S_{{ state.name }} =>
+{% for transition in state.transitions %}
+                {{ "els" if not loop.first }}if {{ guard(transition) }} then
+                    next_state <= S_{{ transition.to }};
+{% if loop.last %}
+                end if;
+{% endif %}
+{% else %}
+                null;
+{% endfor %}
+{% endfor %}
+        end case;
+    end process next_state_logic;
+{% if outputs %}
+
+{% if kind == "moore" %}
+    -- Output logic, depends on the state only
+    output_logic : process (state)
+    begin
+{% for output in outputs %}
+        {{ output }} <= '0';
+{% endfor %}
+        case state is
+{% for state in states %}
+            when S_{{ state.name }} =>
+{% for output in state.outputs %}
+                {{ output }} <= '1';
+{% else %}
+                null;
+{% endfor %}
+{% endfor %}
+        end case;
+    end process output_logic;
+{% else %}
+    -- Output logic, depends on the state and the inputs
+    output_logic : process ({{ sensitivity }})
+    begin
+{% for output in outputs %}
+        {{ output }} <= '0';
+{% endfor %}
+        case state is
+{% for state in states %}
+            when S_{{ state.name }} =>
+{% for transition in state.transitions %}
+                {{ "els" if not loop.first }}if {{ guard(transition) }} then
+{% for output in transition.outputs %}
+                    {{ output }} <= '1';
+{% endfor %}
+{% if loop.last %}
+                end if;
+{% endif %}
+{% else %}
+                null;
+{% endfor %}
+{% endfor %}
+        end case;
+    end process output_logic;
+{% endif %}
+{% endif %}
+
+    is_end <= {% for end_state in end_states %}{% if loop.first %}'1' when {% endif %}state = S_{{ end_state }}{% if not loop.last %} or {% else %} else '0'{% endif %}{% else %}'0'{% endfor %};
+
+end architecture rtl;
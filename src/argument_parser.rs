@@ -4,23 +4,276 @@ This module contains the argument parser for the CLI.
 
 use std::path::PathBuf;
 
-use clap::Parser;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+
+use state_machine_builder::input::Format;
+use state_machine_builder::verilog_generator::Encoding;
 
 #[derive(Parser)]
 #[command(name = "State Machine Builder", author = "Dennis Ostermann", version, about, long_about = None)]
 pub struct Cli {
-    /// The YAML file with the state machine.
-    #[arg(short, long, value_name = "FILE")]
-    pub yaml_file: PathBuf,
+    #[command(subcommand)]
+    pub command: Command,
+
+    /// Read the machine files in this format. If not set, the format is chosen by the
+    /// extension: `.json` is read as JSON, `.toml` as TOML, `.scxml` as SCXML, `.puml` as
+    /// PlantUML, `.kiss2` as KISS2, and all others as YAML.
+    #[arg(long, global = true, value_enum, value_name = "FORMAT")]
+    pub input_format: Option<InputFormat>,
+
+    /// Do not print messages on success.
+    #[arg(short, long, global = true)]
+    pub quiet: bool,
+}
+
+// The doc comments of the variants are the help texts of the subcommands.
+#[derive(Subcommand)]
+pub enum Command {
+    /// Generate code for a machine.
+    #[command(visible_alias = "gen")]
+    Generate(GenerateArgs),
+
+    /// Generate the code for all machines of a project manifest in parallel.
+    Build {
+        /// The project manifest.
+        #[arg(value_name = "MANIFEST", default_value = "smb.toml")]
+        manifest: PathBuf,
+
+        /// The number of machines generated at the same time. If not set, one per CPU.
+        #[arg(short, long, value_name = "N")]
+        jobs: Option<usize>,
+
+        /// Do not write the files, but fail if one of them is missing or out of date.
+        #[arg(long)]
+        check: bool,
+    },
+
+    /// Check machines without generating code.
+    Check {
+        /// The machine files.
+        #[arg(required = true, value_name = "FILE")]
+        files: Vec<PathBuf>,
+
+        /// The format of the findings.
+        #[arg(short, long, value_enum, default_value_t = CheckFormat::Text)]
+        format: CheckFormat,
+    },
+
+    /// Run a machine step by step and print the states and outputs.
+    Simulate {
+        /// The machine file.
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// The inputs of each step, separated by commas, e.g. `i0,i1`. Use `-` for a step
+        /// without inputs. If not set, one step per line is read from the standard input.
+        #[arg(value_name = "STEP")]
+        steps: Vec<String>,
+    },
+
+    /// Print a machine in an exchange format.
+    Export {
+        /// The machine file.
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// The format of the output.
+        #[arg(short, long, value_enum)]
+        format: ExportFormat,
+
+        /// Name of the state machine. If not set, the name of the file without its extension.
+        #[arg(short, long, value_name = "NAME")]
+        name: Option<String>,
+
+        /// The state encoding of the state register (BLIF only).
+        #[arg(long, value_enum, default_value_t = StateEncoding::Binary)]
+        encoding: StateEncoding,
+    },
+
+    /// Rewrite YAML machine files in the canonical layout: fixed field order, quoted
+    /// strings and two-space indentation. Comments are not kept.
+    Fmt {
+        /// The machine files. Files in other formats are converted when printed with `--stdout`.
+        #[arg(required = true, value_name = "FILE")]
+        files: Vec<PathBuf>,
+
+        /// Do not write the files, but fail if one of them is not formatted.
+        #[arg(long, conflicts_with = "stdout")]
+        check: bool,
+
+        /// Print the formatted machines instead of writing the files.
+        #[arg(long)]
+        stdout: bool,
+    },
+
+    /// Compare two machines by their meaning and print the changes. Fails if they differ.
+    Diff {
+        /// The old machine file.
+        #[arg(value_name = "OLD")]
+        old: PathBuf,
+
+        /// The new machine file.
+        #[arg(value_name = "NEW")]
+        new: PathBuf,
+    },
+
+    /// Print the JSON Schema of the YAML, JSON and TOML formats.
+    Schema,
+}
 
-    /// Name of the output file/directory.
-    /// If not set, the name of the input file/directory will be used.
-    /// If the input is a directory, the output will be a directory with the same name.
-    /// If the input is a file, the output will be a file with the same name.
-    #[arg(short, long, value_name = "FILE")]
-    pub output: Option<PathBuf>,
+/// The options of the `generate` subcommand.
+#[derive(Debug, Args)]
+pub struct GenerateArgs {
+    /// The machine file.
+    #[arg(value_name = "FILE")]
+    pub file: PathBuf,
+
+    /// The directory the files are written to.
+    #[arg(short, long, value_name = "DIR", default_value = "./")]
+    pub output: PathBuf,
 
     /// Name of the state machine. This will be used as the name of the C struct.
+    /// If not set, the name of the file without its extension.
     #[arg(short, long, value_name = "NAME")]
-    pub name: String,
+    pub name: Option<String>,
+
+    /// The language of the generated code.
+    #[arg(short, long, value_enum, default_value_t = Language::C)]
+    pub language: Language,
+
+    /// Generate virtual hooks that are called on entering and exiting a state (C++ only).
+    #[arg(long)]
+    pub hooks: bool,
+
+    /// The package of the generated class (Java and Kotlin only).
+    #[arg(long, value_name = "PACKAGE")]
+    pub package: Option<String>,
+
+    /// The state encoding of the state register (SystemVerilog and BLIF only).
+    #[arg(long, value_enum, default_value_t = StateEncoding::Binary)]
+    pub encoding: StateEncoding,
+
+    /// Directory with custom templates. It has the same layout as `resources/templates/<language>`.
+    /// Templates that are not in the directory fall back to the built-in ones.
+    #[arg(short, long, value_name = "DIR")]
+    pub template_dir: Option<PathBuf>,
+
+    /// Keep running and regenerate the code whenever the machine file or a custom
+    /// template changes. Stop with Ctrl+C.
+    #[arg(short, long, conflicts_with_all = ["dry_run", "check"])]
+    pub watch: bool,
+
+    /// Do not write the files, but print the files that would change and the changes.
+    #[arg(long, conflicts_with = "check")]
+    pub dry_run: bool,
+
+    /// Do not write the files, but fail if one of them is missing or out of date.
+    #[arg(long)]
+    pub check: bool,
+}
+
+/// The languages the code can be generated in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Language {
+    /// C99 header and source file.
+    C,
+    /// Header-only C++17 class.
+    Cpp,
+    /// Go package with tests.
+    Go,
+    /// TypeScript module.
+    #[value(alias = "ts")]
+    Typescript,
+    /// Synthesizable SystemVerilog module.
+    #[value(alias = "sv")]
+    Systemverilog,
+    /// VHDL entity and architecture.
+    Vhdl,
+    /// Java class with an enum of the states.
+    Java,
+    /// Kotlin class with a sealed class of the states.
+    Kotlin,
+    /// Rust module with a `Machine` type.
+    #[value(alias = "rs")]
+    Rust,
+    /// SCXML document for statechart tools.
+    Scxml,
+    /// KISS2 state table for logic synthesis tools.
+    Kiss2,
+    /// BLIF netlist of the encoded state table.
+    Blif,
+}
+
+/// The formats of the findings of `check`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CheckFormat {
+    /// One finding per line as `file:line: severity[rule]: message`.
+    Text,
+    /// JSON array of the findings.
+    Json,
+    /// SARIF 2.1.0 log for code scanning tools.
+    Sarif,
+}
+
+/// The formats a machine can be exported to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    /// YAML in the canonical layout of `fmt`.
+    Yaml,
+    /// JSON with the same fields as the YAML file.
+    Json,
+    /// TOML with the same fields as the YAML file.
+    Toml,
+    /// SCXML document for statechart tools.
+    Scxml,
+    /// KISS2 state table for logic synthesis tools.
+    Kiss2,
+    /// BLIF netlist of the encoded state table.
+    Blif,
+}
+
+/// The formats of machine files. The library type `input::Format` does not depend on clap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum InputFormat {
+    Yaml,
+    Json,
+    Toml,
+    Scxml,
+    #[value(name = "plantuml")]
+    PlantUml,
+    Kiss2,
+}
+
+impl From<InputFormat> for Format {
+    fn from(format: InputFormat) -> Self {
+        match format {
+            InputFormat::Yaml => Format::Yaml,
+            InputFormat::Json => Format::Json,
+            InputFormat::Toml => Format::Toml,
+            InputFormat::Scxml => Format::Scxml,
+            InputFormat::PlantUml => Format::PlantUml,
+            InputFormat::Kiss2 => Format::Kiss2,
+        }
+    }
+}
+
+/// The state encodings of the state register, converted to `verilog_generator::Encoding`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum StateEncoding {
+    /// The index of the state.
+    Binary,
+    /// One bit per state.
+    OneHot,
+    /// The Gray code of the index, so that consecutive states differ in one bit.
+    Gray,
+}
+
+impl From<StateEncoding> for Encoding {
+    fn from(encoding: StateEncoding) -> Self {
+        match encoding {
+            StateEncoding::Binary => Encoding::Binary,
+            StateEncoding::OneHot => Encoding::OneHot,
+            StateEncoding::Gray => Encoding::Gray,
+        }
+    }
 }
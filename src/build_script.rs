@@ -0,0 +1,205 @@
+/*!
+Helper for build scripts
+
+This module generates a state machine from a downstream `build.rs`. The machine file is
+read, all checks are run and the Rust or C code is written into `OUT_DIR`. Cargo is told
+to rerun the build script when the machine file or the templates change.
+
+```no_run
+// build.rs
+use state_machine_builder::build_script::{self, Target};
+
+fn main() {
+    build_script::generate("machines/motor_sm.yaml", Target::Rust).unwrap();
+}
+```
+
+The generated module is then included with
+`include!(concat!(env!("OUT_DIR"), "/motor_sm.rs"));`.
+*/
+
+use std::path::{Path, PathBuf};
+
+use crate::files::Files;
+use crate::input::{self, Format};
+use crate::templates::Templates;
+use crate::{c_generator, checks, rust_generator};
+
+/// The language of the generated code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    /// A Rust module, see [`rust_generator`].
+    Rust,
+    /// A C header and source file, see [`c_generator`].
+    C,
+}
+
+/// Generates the code for a machine file into `OUT_DIR`. The name of the file
+/// without its extension is used as the name of the machine.
+pub fn generate(path: impl AsRef<Path>, target: Target) -> Result<(), String> {
+    BuildScript::new(path, target).generate()
+}
+
+/// Generates the code for a machine file. The name, the template directory and the
+/// output directory can be changed before calling `generate`.
+#[derive(Debug)]
+pub struct BuildScript {
+    path: PathBuf,
+    target: Target,
+    name: Option<String>,
+    template_dir: Option<PathBuf>,
+    out_dir: Option<PathBuf>,
+}
+
+impl BuildScript {
+    /// Creates a build script step for the machine file.
+    pub fn new(path: impl AsRef<Path>, target: Target) -> Self {
+        BuildScript {
+            path: path.as_ref().to_path_buf(),
+            target,
+            name: None,
+            template_dir: None,
+            out_dir: None,
+        }
+    }
+
+    /// Sets the name of the machine (default: the file name without its extension).
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Sets a directory with custom templates.
+    pub fn template_dir(mut self, dir: impl AsRef<Path>) -> Self {
+        self.template_dir = Some(dir.as_ref().to_path_buf());
+        self
+    }
+
+    /// Sets the output directory (default: `OUT_DIR`).
+    pub fn out_dir(mut self, dir: impl AsRef<Path>) -> Self {
+        self.out_dir = Some(dir.as_ref().to_path_buf());
+        self
+    }
+
+    /// Reads the machine, runs all checks and writes the code. Prints the
+    /// `cargo:rerun-if-changed` lines for the machine file and the templates.
+    pub fn generate(self) -> Result<(), String> {
+        println!("cargo:rerun-if-changed={}", self.path.display());
+        if let Some(dir) = &self.template_dir {
+            println!("cargo:rerun-if-changed={}", dir.display());
+        }
+
+        let name = match self.name {
+            Some(name) => name,
+            None => self
+                .path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .map(str::to_string)
+                .ok_or_else(|| format!("{} has no file name.", self.path.display()))?,
+        };
+        checks::validate_machine_name(&name).map_err(|e| {
+            format!(
+                "{}: {} Set another name with BuildScript::name.",
+                self.path.display(),
+                e
+            )
+        })?;
+        let out_dir = match self.out_dir {
+            Some(dir) => dir,
+            None => std::env::var_os("OUT_DIR")
+                .map(PathBuf::from)
+                .ok_or("OUT_DIR is not set. Is this running in a build script?")?,
+        };
+
+        let contents = std::fs::read_to_string(&self.path)
+            .map_err(|e| format!("Cannot read {}: {}", self.path.display(), e))?;
+        let state_machine = input::parse(&contents, Format::from_path(&self.path))
+            .map_err(|e| format!("{}: {}", self.path.display(), e))?;
+        checks::validate_state_machine(&state_machine)
+            .map_err(|e| format!("{}: {}", self.path.display(), e))?;
+
+        let language = match self.target {
+            Target::Rust => rust_generator::LANGUAGE,
+            Target::C => c_generator::LANGUAGE,
+        };
+        let templates = Templates::new(language, self.template_dir.as_deref())?;
+        let files = Files::new(out_dir);
+        let files = match self.target {
+            Target::Rust => rust_generator::generate(&name, &state_machine, &templates, files)?,
+            Target::C => c_generator::generate(&name, &state_machine, &templates, files)?,
+        };
+
+        files.write().map_err(|e| e.to_string())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_support::temp_dir;
+
+    #[test]
+    fn test_generate_rust_and_c() {
+        let dir = temp_dir("build_script");
+        let machine = dir.join("motor_sm.yaml");
+        std::fs::write(&machine, include_str!("../resources/test_moore.yaml")).unwrap();
+
+        BuildScript::new(&machine, Target::Rust)
+            .out_dir(&dir)
+            .generate()
+            .unwrap();
+        BuildScript::new(&machine, Target::C)
+            .name("motor")
+            .out_dir(&dir)
+            .generate()
+            .unwrap();
+
+        let source = std::fs::read_to_string(dir.join("motor_sm.rs")).unwrap();
+        assert!(source.contains("pub mod motor_sm {"));
+        assert!(dir.join("motor.h").exists());
+        assert!(dir.join("motor.c").exists());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_generate_runs_checks() {
+        let dir = temp_dir("build_script_checks");
+        let machine = dir.join("broken.yaml");
+        let yaml_str = include_str!("../resources/test_moore.yaml")
+            .replace("end_states:\n  - \"q3\"", "end_states:\n  - \"q4\"");
+        std::fs::write(&machine, yaml_str).unwrap();
+
+        let error = BuildScript::new(&machine, Target::Rust)
+            .out_dir(&dir)
+            .generate()
+            .unwrap_err();
+
+        assert!(error.starts_with(machine.to_str().unwrap()));
+        assert!(!dir.join("broken.rs").exists());
+        let machine = dir.join("motor-sm.yaml");
+        std::fs::write(&machine, include_str!("../resources/test_moore.yaml")).unwrap();
+        let error = BuildScript::new(&machine, Target::Rust)
+            .out_dir(&dir)
+            .generate()
+            .unwrap_err();
+        assert!(error.ends_with(
+            "motor-sm.yaml: The name \"motor-sm\" is not an identifier. \
+             Set another name with BuildScript::name."
+        ));
+        assert!(!dir.join("motor-sm.rs").exists());
+        assert!(BuildScript::new(&machine, Target::Rust)
+            .name("motor_sm")
+            .out_dir(&dir)
+            .generate()
+            .is_ok());
+        assert!(BuildScript::new(dir.join("missing.yaml"), Target::C)
+            .out_dir(&dir)
+            .generate()
+            .is_err());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,224 @@
+/*!
+Builder for state machines
+
+This module builds a Moore or Mealy machine in code instead of reading it from a file.
+A machine with outputs on its transitions is a Mealy machine, all others are Moore machines.
+The inputs and outputs used in the transitions are added to the alphabets.
+*/
+
+use crate::checks;
+use crate::input::{Diagram, Transition};
+use crate::state_machines::{StateMachine, Variable, VariableType, VariableValue};
+
+/// Builds a Moore or Mealy machine. The first state is the start state unless
+/// `start_state` is set.
+///
+/// ```
+/// use state_machine_builder::{MachineBuilder, StateMachine};
+///
+/// let machine = MachineBuilder::new()
+///     .state("q1")
+///     .state("q2")
+///     .transition_with_output("q1", ["i0", "!i1"], "q2", "o0")
+///     .end_state("q2")
+///     .build()
+///     .unwrap();
+///
+/// assert!(matches!(machine, StateMachine::Mealy(_)));
+/// ```
+#[derive(Debug, Default)]
+pub struct MachineBuilder {
+    states: Vec<String>,
+    inputs: Vec<String>,
+    outputs: Vec<String>,
+    start_state: Option<String>,
+    end_states: Vec<String>,
+    state_outputs: Vec<(String, Vec<String>)>,
+    transitions: Vec<Transition>,
+    variables: Vec<Variable>,
+}
+
+impl MachineBuilder {
+    /// Creates a builder for an empty machine.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a state.
+    pub fn state(mut self, name: impl Into<String>) -> Self {
+        self.states.push(name.into());
+        self
+    }
+
+    /// Adds an input symbol. Inputs used in transitions are added automatically.
+    pub fn input(mut self, name: impl Into<String>) -> Self {
+        self.inputs.push(name.into());
+        self
+    }
+
+    /// Adds an output symbol. Outputs used in states or transitions are added automatically.
+    pub fn output(mut self, name: impl Into<String>) -> Self {
+        self.outputs.push(name.into());
+        self
+    }
+
+    /// Sets the start state.
+    pub fn start_state(mut self, name: impl Into<String>) -> Self {
+        self.start_state = Some(name.into());
+        self
+    }
+
+    /// Adds an end state.
+    pub fn end_state(mut self, name: impl Into<String>) -> Self {
+        self.end_states.push(name.into());
+        self
+    }
+
+    /// Adds a transition. The read symbols can be negated with `!`, e.g. `["i0", "!i1"]`.
+    /// The transitions of a state are checked in the order they are added.
+    pub fn transition<S: Into<String>>(
+        self,
+        from: impl Into<String>,
+        read_symbols: impl IntoIterator<Item = S>,
+        to: impl Into<String>,
+    ) -> Self {
+        self.add_transition(from.into(), read_symbols, to.into(), Vec::new())
+    }
+
+    /// Adds a transition with an output (Mealy machine).
+    pub fn transition_with_output<S: Into<String>>(
+        self,
+        from: impl Into<String>,
+        read_symbols: impl IntoIterator<Item = S>,
+        to: impl Into<String>,
+        output: impl Into<String>,
+    ) -> Self {
+        self.add_transition(from.into(), read_symbols, to.into(), vec![output.into()])
+    }
+
+    /// Sets the outputs of a state (Moore machine).
+    pub fn state_outputs<S: Into<String>>(
+        mut self,
+        state: impl Into<String>,
+        outputs: impl IntoIterator<Item = S>,
+    ) -> Self {
+        let outputs = outputs.into_iter().map(Into::into).collect();
+        self.state_outputs.push((state.into(), outputs));
+        self
+    }
+
+    /// Adds an extended state variable. Without an initial value, it starts with false or 0.
+    pub fn variable(
+        mut self,
+        name: impl Into<String>,
+        data_type: VariableType,
+        initial: Option<VariableValue>,
+    ) -> Self {
+        self.variables.push(Variable {
+            name: name.into(),
+            data_type,
+            initial,
+        });
+        self
+    }
+
+    /// Builds the machine and runs all checks on it.
+    pub fn build(self) -> Result<StateMachine, String> {
+        let start_state = self
+            .start_state
+            .or_else(|| self.states.first().cloned())
+            .ok_or("The machine does not have any states.")?;
+
+        let state_machine = Diagram {
+            states: self.states,
+            inputs: self.inputs,
+            outputs: self.outputs,
+            start_state,
+            end_states: self.end_states,
+            state_outputs: self.state_outputs,
+            transitions: self.transitions,
+            variables: self.variables,
+        }
+        .into_machine()?;
+
+        checks::validate_state_machine(&state_machine)?;
+        Ok(state_machine)
+    }
+
+    fn add_transition<S: Into<String>>(
+        mut self,
+        from: String,
+        read_symbols: impl IntoIterator<Item = S>,
+        to: String,
+        outputs: Vec<String>,
+    ) -> Self {
+        self.transitions.push(Transition {
+            from,
+            to,
+            guard: read_symbols.into_iter().map(Into::into).collect(),
+            outputs,
+        });
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::model::Model;
+    use crate::test_support::parse;
+
+    #[test]
+    fn test_build_moore() {
+        let machine = MachineBuilder::new()
+            .state("q1")
+            .state("q2")
+            .state("q3")
+            .output("o5")
+            .transition("q1", ["i0", "i1"], "q2")
+            .transition("q2", ["i1"], "q3")
+            .state_outputs("q1", ["o5", "o6"])
+            .end_state("q3")
+            .build()
+            .unwrap();
+
+        let original = Model::new("test", &parse(include_str!("../resources/test_moore.yaml")));
+        let model = Model::new("test", &machine);
+        assert_eq!(format!("{:?}", model), format!("{:?}", original));
+    }
+
+    #[test]
+    fn test_build_variables_and_start_state() {
+        let machine = MachineBuilder::new()
+            .state("idle")
+            .state("running")
+            .start_state("running")
+            .variable("counter", VariableType::Uint, Some(VariableValue::Int(3)))
+            .build()
+            .unwrap();
+
+        match machine {
+            StateMachine::Moore(m) => {
+                assert_eq!(m.start_state, "running");
+                assert_eq!(m.variables[0].name, "counter");
+            }
+            _ => panic!("Wrong state machine type."),
+        }
+    }
+
+    #[test]
+    fn test_build_runs_checks() {
+        let duplicate = MachineBuilder::new().state("q1").state("q1").build();
+        let end_state = MachineBuilder::new().state("q1").end_state("q2").build();
+        let mixed = MachineBuilder::new()
+            .state("q1")
+            .state_outputs("q1", ["o0"])
+            .transition_with_output("q1", ["i0"], "q1", "o1")
+            .build();
+
+        assert!(duplicate.is_err());
+        assert!(end_state.is_err());
+        assert!(mixed.is_err());
+        assert!(MachineBuilder::new().build().is_err());
+    }
+}
@@ -8,199 +8,226 @@ The generated code is written to a file or directory.
 */
 
 use crate::files::Files;
-use crate::state_machines::{Machine, MooreMachine, StateMachine};
+use crate::model::Model;
+use crate::state_machines::StateMachine;
+use crate::templates::Templates;
 
-/// The C files. Contains the header and source file content as strings.
-#[derive(Debug)]
-struct CFiles {
-    header: String, // The header file.
-    source: String, // The source file.
-}
+/// The language of the templates in `resources/templates`.
+pub const LANGUAGE: &str = "c";
 
 /// Generates the C code for the state machine. The code is written to a file or directory.
 /// The name is used for the file name.
+/// The templates are taken from `templates`.
 pub fn generate(
     name: &str,
     state_machine: &StateMachine,
+    templates: &Templates,
     mut files: Files,
 ) -> Result<Files, String> {
-    let mut c_files = CFiles {
-        header: include_str!("../resources/templates/c/header.h").to_string(),
-        source: include_str!("../resources/templates/c/source.c").to_string(),
-    };
-
-    // Replace the name.
-    replace_code(&mut c_files, "NAME", &name);
-
-    match &state_machine {
-        StateMachine::Moore(m) => {
-            replace_header_code(&m, &mut c_files);
-            reset_output(&mut c_files, &m);
-            end_states(&mut c_files, &m);
-            let cases = moore::all_cases(&m);
-            replace_code(&mut c_files, "CASE", &cases);
-        }
-        StateMachine::Mealy(m) => {
-            replace_header_code(&m, &mut c_files);
-            reset_output(&mut c_files, &m);
-            end_states(&mut c_files, &m);
-            // TODO Implement Mealy machine.
-            return Err("Mealy machines are not supported yet.".to_string());
-        }
-    }
+    let model = Model::new(name, state_machine);
 
-    files.add_file(format!("{}.h", name), c_files.header);
-    files.add_file(format!("{}.c", name), c_files.source);
+    files.add_file(format!("{}.h", name), templates.render("header.h", &model)?);
+    files.add_file(format!("{}.c", name), templates.render("source.c", &model)?);
 
     Ok(files)
 }
 
-/// Replaces the code in the C files.
-fn replace_code(c_file: &mut CFiles, code: &str, replacement: &str) {
-    let code = format!("/*CODE:{}*/", code);
-    c_file.header = c_file.header.replace(&code, replacement);
-    c_file.source = c_file.source.replace(&code, replacement);
-}
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_support::{parse, run_tool, temp_dir};
+    use std::path::{Path, PathBuf};
+    use std::process::Command;
+
+    // A machine without outputs, end states, and guards.
+    const MINIMAL_YAML: &str = "
+states: [idle, running]
+input_alphabet: []
+transitions:
+  - current_state: idle
+    read_symbol: []
+    new_state: running
+output_function: []
+start_state: idle
+end_states: []
+output_alphabet: []
+";
+
+    fn render(yaml_str: &str, template: &str) -> String {
+        let templates = Templates::new(LANGUAGE, None).unwrap();
+        templates
+            .render(template, &Model::new("test", &parse(yaml_str)))
+            .unwrap()
+    }
 
-/// Replaces the code in the header file. This includes the name, states, inputs, outputs, and initial state.
-fn replace_header_code<T, O>(m: &Machine<T, O>, c_files: &mut CFiles) {
-    set_declaration(c_files, &m.states, "STATES_ENUM", "", ',');
-    set_declaration(
-        c_files,
-        &m.input_alphabet,
-        "INPUTS_DECLARATION",
-        "bool",
-        ';',
-    );
-    set_declaration(
-        c_files,
-        &m.output_alphabet,
-        "OUTPUTS_DECLARATION",
-        "bool",
-        ';',
-    );
-    replace_code(c_files, "INITIAL_STATE", &m.start_state);
-}
+    /// Generates the C code into a unique directory in the temp directory.
+    fn generate_files(name: &str, yaml_str: &str) -> PathBuf {
+        let dir = temp_dir(&format!("c_{}", name));
+        let templates = Templates::new(LANGUAGE, None).unwrap();
+        let files = generate(name, &parse(yaml_str), &templates, Files::new(dir.clone())).unwrap();
+        files.write().unwrap();
+        dir
+    }
 
-/// Sets the declaration of the inputs or outputs. This is used for the header file.
-fn set_declaration(
-    c_file: &mut CFiles,
-    inputs: &[String],
-    to_replace: &str,
-    data_type: &str,
-    separator: char,
-) {
-    let mut declaration = String::new();
-
-    for input in inputs {
-        declaration.push_str(&format!("    {} {}{}\n", data_type, input, separator));
+    /// Compiles a C file to an object file next to it.
+    fn compile(file: &Path) {
+        let object = file.with_extension("o");
+        let mut args = vec!["-Wall", "-Wextra", "-Werror", "-std=c99", "-c"];
+        args.extend([file.to_str().unwrap(), "-o", object.to_str().unwrap()]);
+        run_tool("CC", "cc", &args);
     }
 
-    replace_code(c_file, &to_replace, &declaration);
-}
+    /// Compiles the generated source as C99 with all warnings as errors.
+    fn assert_compiles(name: &str, yaml_str: &str) {
+        let dir = generate_files(name, yaml_str);
 
-fn reset_output<T, O>(c_file: &mut CFiles, m: &Machine<T, O>) {
-    let mut reset = String::new();
+        compile(&dir.join(format!("{}.c", name)));
 
-    for output in &m.output_alphabet {
-        reset.push_str(&format!("    output->{} = false;\n", output));
+        std::fs::remove_dir_all(dir).unwrap();
     }
 
-    replace_code(c_file, "RESET_OUTPUT", &reset);
-}
-
-fn end_states<T, O>(c_file: &mut CFiles, m: &Machine<T, O>) {
-    let end_states = m
-        .end_states
-        .iter()
-        .map(|s| format!("(state == {})", s))
-        .collect::<Vec<String>>()
-        .join(" || ");
-
-    if end_states.is_empty() {
-        replace_code(c_file, "END_STATE", "false");
-    } else {
-        replace_code(c_file, "END_STATE", &end_states);
+    #[test]
+    fn test_moore_source() {
+        let source = render(include_str!("../resources/test_moore.yaml"), "source.c");
+
+        assert!(source.contains("#include \"test.h\""));
+        assert!(source.contains(
+            "case test_q1:\n        ctx->output.o5 = true;\n        ctx->output.o6 = true;"
+        ));
+        assert!(source.contains("if (input.i0 && input.i1)"));
+        assert!(source.contains("return (ctx->state == test_q3);"));
+        assert!(!source.contains("{{"));
     }
-}
-
-mod moore {
-    use super::*;
-    use crate::state_machines::MooreTransition;
-
-    /// Generates the code for the states. This is used for the source file.
-    pub fn all_cases(m: &MooreMachine) -> String {
-        let case = include_str!("../resources/templates/c/case.c");
-        let mut code_code = String::new();
 
-        for state in &m.states {
-            let mut s = case.replace("/*CODE:CASE_NAME*/", state);
+    #[test]
+    fn test_mealy_source() {
+        let source = render(include_str!("../resources/test_mealy.yaml"), "source.c");
 
-            let case_code = case_code(&m, state);
-            s = s.replace("/*CODE:CASE_CODE*/", &case_code);
+        assert!(source
+            .contains("if (input.i0 && !input.i1)\n        {\n            ctx->output.o0 = true;"));
+        assert!(source.contains("else if (!input.i0)"));
+    }
 
-            code_code.push_str(&s);
-            code_code.push('\n');
-        }
+    #[test]
+    fn test_header() {
+        let header = render(include_str!("../resources/test_moore.yaml"), "header.h");
 
-        code_code
+        assert!(header.contains("    test_q1,\n    test_q2,\n    test_q3,\n"));
+        assert!(header.contains("    bool i0;\n    bool i1;\n"));
+        assert!(header.contains("    bool o5;\n    bool o6;\n"));
     }
 
-    fn case_code(m: &MooreMachine, state: &str) -> String {
-        let mut code = include_str!("../resources/templates/c/moore/case_code.c").to_string();
-
-        // Outputs
-        // TODO Check if only one output function fits the current state.
-        let outputs = m
-            .output_function
-            .iter()
-            .filter(|o| o.current_state == *state)
-            // take only the first output function
-            .take(1)
-            .map(|o| {
-                o.output_symbol
-                    .iter()
-                    .map(|s| format!("output->{} = true;", s))
-                    .collect::<Vec<String>>()
-                    .join("\n        ")
-            })
-            .collect::<Vec<String>>();
-
-        code = code.replace(
-            "/*CODE:SET_OUTPUT*/",
-            &outputs.first().unwrap_or(&String::new()),
-        );
+    #[test]
+    fn test_compile_moore() {
+        assert_compiles("moore", include_str!("../resources/test_moore.yaml"));
+    }
 
-        // Ifs
-        let transitions = m
-            .transitions
-            .iter()
-            .filter(|t| t.current_state == *state)
-            .collect::<Vec<&MooreTransition>>();
+    #[test]
+    fn test_compile_mealy() {
+        assert_compiles("mealy", include_str!("../resources/test_mealy.yaml"));
+    }
 
-        let mut transitions_code = String::new();
-        for transition in transitions {
-            let mut if_code = include_str!("../resources/templates/c/moore/case_if.c").to_string();
+    #[test]
+    fn test_compile_minimal() {
+        assert_compiles("minimal", MINIMAL_YAML);
+    }
 
-            // TODO Make false checks possible.
-            let conditions = transition
-                .read_symbol
-                .iter()
-                .map(|s| format!("input.{}", s))
-                .collect::<Vec<String>>()
-                .join(" && ");
+    #[test]
+    fn test_compile_variables() {
+        let yaml_str = format!(
+            "{}variables:
+  - name: counter
+    type: uint
+  - name: limit
+    type: int
+    initial: -3
+  - name: enabled
+    type: bool
+    initial: true
+  - name: gain
+    type: float
+    initial: 1.5
+",
+            include_str!("../resources/test_moore.yaml")
+        );
 
-            if_code = if_code.replace("/*CODE:IF*/", &conditions);
+        assert_compiles("variables", &yaml_str);
+    }
 
-            let new_state = format!("*state = {};", &transition.new_state);
-            if_code = if_code.replace("/*CODE:SET_NEW_STATE*/", &new_state);
+    #[test]
+    fn test_run_independent_instances() {
+        // Both machines use the same state names and are linked into the same program.
+        let dir = generate_files("first", include_str!("../resources/test_moore.yaml"));
+        let second = generate_files("second", include_str!("../resources/test_moore.yaml"));
+        for file in ["second.h", "second.c"] {
+            std::fs::copy(second.join(file), dir.join(file)).unwrap();
+        }
+        std::fs::write(
+            dir.join("main.c"),
+            r#"#include "first.h"
+#include "first.h"
+#include "second.h"
+
+int main(void)
+{
+    struct SM_first a;
+    struct SM_first b;
+    struct SM_second c;
+    struct SMInput_first input = {true, true};
+
+    sm_first_init(&a);
+    sm_first_init(&b);
+    sm_second_init(&c);
+
+    sm_first_step(&a, input);
+    if (a.state != first_q2 || a.previous_state != first_q1 || !a.output.o5)
+        return 1;
+    if (b.state != first_q1)
+        return 2;
+
+    input.i0 = false;
+    sm_first_step(&a, input);
+    if (!sm_first_is_end_state(&a) || sm_first_is_end_state(&b))
+        return 3;
+
+    sm_first_reset(&a);
+    if (a.state != first_q1 || a.output.o5)
+        return 4;
+
+    return sm_second_is_end_state(&c) ? 5 : 0;
+}
+"#,
+        )
+        .unwrap();
 
-            transitions_code.push_str(&if_code);
-            transitions_code.push('\n');
+        for file in ["first.c", "second.c", "main.c"] {
+            compile(&dir.join(file));
         }
+        let executable = dir.join("main");
+        let mut args = ["first.o", "second.o", "main.o"]
+            .map(|o| dir.join(o))
+            .to_vec();
+        args.extend(["-o".into(), executable.clone()]);
+        run_tool("CC", "cc", &args);
+
+        let status = Command::new(&executable).status().unwrap();
+        assert_eq!(status.code(), Some(0));
+
+        std::fs::remove_dir_all(dir).unwrap();
+        std::fs::remove_dir_all(second).unwrap();
+    }
+
+    #[test]
+    fn test_header_compiles_as_cpp() {
+        let dir = generate_files("cpp", include_str!("../resources/test_moore.yaml"));
+        let main = dir.join("main.cpp");
+        std::fs::write(&main, "#include \"cpp.h\"\n").unwrap();
 
-        code = code.replace("/*CODE:CASE_IFS*/", &transitions_code);
+        let object = main.with_extension("o");
+        let args = ["-Wall", "-Wextra", "-Werror", "-c"];
+        let paths = [main.to_str().unwrap(), "-o", object.to_str().unwrap()];
+        run_tool("CXX", "c++", &[&args[..], &paths[..]].concat());
 
-        code
+        std::fs::remove_dir_all(dir).unwrap();
     }
 }
@@ -4,10 +4,14 @@ This module contains functions to check the validity of the state machine.
 
 use std::collections::HashSet;
 
-use crate::state_machines::Machine;
+use heck::{ToLowerCamelCase, ToShoutySnakeCase, ToSnakeCase, ToUpperCamelCase};
+use serde::Serialize;
+
+use crate::model::Model;
+use crate::state_machines::{Machine, StateMachine, VariableType, VariableValue};
 
 /// Check if the elements in the state machine are unique.
-/// The elements are the states, input alphabet, and output alphabet.
+/// The elements are the states, input alphabet, output alphabet, and variables.
 ///
 /// # Arguments
 ///
@@ -18,41 +22,91 @@ use crate::state_machines::Machine;
 /// * `Ok(())` if the elements are unique.
 /// * `Err(String)` if the elements are not unique. The error message contains the duplicate elements.
 pub fn validate_unique_elements<T, U>(machine: &Machine<T, U>) -> Result<(), String> {
-    let mut unique_elements: HashSet<String> = HashSet::new();
-    let mut duplicates: HashSet<String> = HashSet::new();
+    let duplicates = duplicates(machine);
 
-    for state in &machine.states {
-        if unique_elements.contains(state) {
-            duplicates.insert(state.to_string());
-        }
-        unique_elements.insert(state.to_string());
+    if !duplicates.is_empty() {
+        return Err(format!(
+            "Duplicate elements found: {}",
+            duplicates.join(", ")
+        ));
     }
+    Ok(())
+}
 
-    for alphabet in &machine.input_alphabet {
-        if unique_elements.contains(alphabet) {
-            duplicates.insert(alphabet.to_string());
+/// Returns the elements that appear more than once, in the order of their second appearance.
+/// The elements are the states, input alphabet, output alphabet, and variables.
+fn duplicates<T, U>(machine: &Machine<T, U>) -> Vec<String> {
+    let mut unique_elements: HashSet<&String> = HashSet::new();
+    let mut duplicates: Vec<String> = Vec::new();
+
+    let elements = machine
+        .states
+        .iter()
+        .chain(&machine.input_alphabet)
+        .chain(&machine.output_alphabet)
+        .chain(machine.variables.iter().map(|variable| &variable.name));
+    for element in elements {
+        if !unique_elements.insert(element) && !duplicates.contains(element) {
+            duplicates.push(element.to_string());
         }
-        unique_elements.insert(alphabet.to_string());
     }
+    duplicates
+}
 
-    for symbol in &machine.output_alphabet {
-        if unique_elements.contains(symbol) {
-            duplicates.insert(symbol.to_string());
+/// The conversions of names by the templates of the backends.
+const CASE_CONVERSIONS: [fn(&str) -> String; 4] = [
+    |name| name.to_snake_case(),
+    |name| name.to_shouty_snake_case(),
+    |name| name.to_lower_camel_case(),
+    |name| name.to_upper_camel_case(),
+];
+
+/// The members of the generated machines next to the variables, e.g. of `struct SM_<name>`
+/// in C, in snake case.
+const MACHINE_MEMBERS: [&str; 3] = ["state", "previous_state", "output"];
+
+/// Returns an error and the later name for every pair of different names that become
+/// the same name in one of the case conversions of the backends, e.g. `q_1` and `q1`,
+/// which are both `Q1` in Go. The names are the states, symbols, and variables.
+/// Variables must not have the name of a member of the generated machine either.
+fn name_collisions<T, U>(machine: &Machine<T, U>) -> Vec<(String, String)> {
+    let names: Vec<&String> = machine
+        .states
+        .iter()
+        .chain(&machine.input_alphabet)
+        .chain(&machine.output_alphabet)
+        .chain(machine.variables.iter().map(|variable| &variable.name))
+        .collect();
+
+    let mut collisions = Vec::new();
+    for (index, name) in names.iter().enumerate() {
+        let earlier = names[..index].iter().find(|earlier| {
+            *earlier != name
+                && CASE_CONVERSIONS
+                    .iter()
+                    .any(|convert| convert(earlier) == convert(name))
+        });
+        if let Some(earlier) = earlier {
+            if !collisions.iter().any(|(_, n)| n == *name) {
+                let message = format!(
+                    "The names {} and {} are the same in the generated code.",
+                    earlier, name
+                );
+                collisions.push((message, name.to_string()));
+            }
         }
-        unique_elements.insert(symbol.to_string());
     }
 
-    if duplicates.len() > 0 {
-        return Err(format!(
-            "Duplicate elements found: {}",
-            duplicates
-                .iter()
-                .map(|s| s.as_str())
-                .collect::<Vec<&str>>()
-                .join(", ")
-        ));
+    for variable in &machine.variables {
+        if MACHINE_MEMBERS.contains(&variable.name.to_snake_case().as_str()) {
+            let message = format!(
+                "The variable {} has the same name as a member of the generated machine.",
+                variable.name
+            );
+            collisions.push((message, variable.name.clone()));
+        }
     }
-    Ok(())
+    collisions
 }
 
 /// Check if the end states are valid.
@@ -68,72 +122,413 @@ pub fn validate_unique_elements<T, U>(machine: &Machine<T, U>) -> Result<(), Str
 /// * `Ok(())` if the end states are valid.
 /// * `Err(String)` if the end states are not valid.
 pub fn validate_end_states<T, U>(machine: &Machine<T, U>) -> Result<(), String> {
-    let states: HashSet<&String> = machine.states.iter().collect();
-    let end_states: HashSet<&String> = machine.end_states.iter().collect();
-
-    if !end_states.is_subset(&states) {
+    if !unknown_end_states(machine).is_empty() {
         return Err("The end states must be a subset of the states.".to_string());
     }
 
     Ok(())
 }
 
-/// Check if none of the states or symbols starts with a number or are empty.
+/// Returns the end states that are not states.
+fn unknown_end_states<T, U>(machine: &Machine<T, U>) -> Vec<&String> {
+    let states: HashSet<&String> = machine.states.iter().collect();
+
+    machine
+        .end_states
+        .iter()
+        .filter(|end_state| !states.contains(end_state))
+        .collect()
+}
+
+/// Check if none of the states, symbols, or variables starts with a number or are empty.
 /// This is to prevent the user from using numbers as states or symbols.
 /// The empty string is not allowed as a state or symbol.
 /// This is to prevent the user from using the empty string as a state or symbol.
 /// C and many other languages do not allow variable names to start with a number.
 pub fn validate_legal_variable_name<T, U>(machine: &Machine<T, U>) -> Result<(), String> {
-    for state in &machine.states {
-        if let Some(first_char) = state.chars().next() {
-            if first_char.is_numeric() {
-                return Err(format!("The state {} starts with a number.", state));
+    match illegal_names(machine).into_iter().next() {
+        Some((error, _)) => Err(error),
+        None => Ok(()),
+    }
+}
+
+/// Returns an error and the name of every state, symbol, or variable that starts with a
+/// number or is empty.
+fn illegal_names<T, U>(machine: &Machine<T, U>) -> Vec<(String, String)> {
+    let names = machine
+        .states
+        .iter()
+        .map(|state| ("state", state))
+        .chain(
+            machine
+                .input_alphabet
+                .iter()
+                .map(|symbol| ("input", symbol)),
+        )
+        .chain(
+            machine
+                .output_alphabet
+                .iter()
+                .map(|symbol| ("output", symbol)),
+        )
+        .chain(machine.variables.iter().map(|v| ("variable", &v.name)));
+
+    // TODO check if strings do not contain spaces
+    names
+        .filter_map(|(kind, name)| match name.chars().next() {
+            Some(first_char) if first_char.is_numeric() => Some((
+                format!("The {} {} starts with a number.", kind, name),
+                name.clone(),
+            )),
+            Some(_) => None,
+            None => Some((format!("The {} {} is empty.", kind, name), name.clone())),
+        })
+        .collect()
+}
+
+/// Check if the name of a machine is an identifier: letters, digits, and underscores,
+/// not starting with a digit. The name is used for the files, modules, and types of the
+/// generated code.
+///
+/// # Arguments
+///
+/// * `name` - The name of the machine.
+///
+/// # Returns
+///
+/// * `Ok(())` if the name is an identifier.
+/// * `Err(String)` if the name is empty or contains other characters.
+pub fn validate_machine_name(name: &str) -> Result<(), String> {
+    let mut chars = name.chars();
+    let identifier = chars
+        .next()
+        .is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !identifier {
+        return Err(format!("The name \"{}\" is not an identifier.", name));
+    }
+    Ok(())
+}
+
+/// Run all checks on a Moore or Mealy machine.
+///
+/// # Arguments
+///
+/// * `state_machine` - The State Machine to check.
+///
+/// # Returns
+///
+/// * `Ok(())` if no rule of `RULES` finds an error. Warnings are ignored.
+/// * `Err(String)` with the first error of `find_all`.
+pub fn validate_state_machine(state_machine: &StateMachine) -> Result<(), String> {
+    match find_all(state_machine)
+        .into_iter()
+        .find(|finding| finding.severity == Severity::Error)
+    {
+        Some(finding) => Err(finding.message),
+        None => Ok(()),
+    }
+}
+
+/// How serious a finding is. Errors fail the `check` subcommand, warnings do not.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// A problem found by a rule.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Finding {
+    /// The id of the rule that found the problem.
+    pub rule: &'static str,
+    pub severity: Severity,
+    pub message: String,
+    /// The state, symbol, or variable the problem is about.
+    pub element: Option<String>,
+}
+
+/// A rule of `find_all`. Its check returns the message and the element of every problem.
+#[derive(Debug)]
+pub struct Rule {
+    pub id: &'static str,
+    pub severity: Severity,
+    pub description: &'static str,
+    check: fn(&StateMachine) -> Vec<(String, String)>,
+}
+
+/// All rules in the order they are run. `validate_state_machine`, the `check` subcommand,
+/// and the `state_machine!` macro use this list.
+pub const RULES: [Rule; 8] = [
+    Rule {
+        id: "legal-names",
+        severity: Severity::Error,
+        description: "States, symbols, and variables must not be empty or start with a number.",
+        check: |state_machine| match state_machine {
+            StateMachine::Moore(machine) => illegal_names(machine),
+            StateMachine::Mealy(machine) => illegal_names(machine),
+        },
+    },
+    Rule {
+        id: "unique-elements",
+        severity: Severity::Error,
+        description: "States, symbols, and variables must have unique names.",
+        check: |state_machine| {
+            let duplicates = match state_machine {
+                StateMachine::Moore(machine) => duplicates(machine),
+                StateMachine::Mealy(machine) => duplicates(machine),
+            };
+            duplicates
+                .into_iter()
+                .map(|duplicate| {
+                    (
+                        format!("Duplicate elements found: {}", duplicate),
+                        duplicate,
+                    )
+                })
+                .collect()
+        },
+    },
+    Rule {
+        id: "name-collisions",
+        severity: Severity::Error,
+        description: "Names must stay different when the backends convert their case, and variables must not be named like members of the machine.",
+        check: |state_machine| match state_machine {
+            StateMachine::Moore(machine) => name_collisions(machine),
+            StateMachine::Mealy(machine) => name_collisions(machine),
+        },
+    },
+    Rule {
+        id: "end-states",
+        severity: Severity::Error,
+        description: "The end states must be states.",
+        check: |state_machine| {
+            let end_states = match state_machine {
+                StateMachine::Moore(machine) => unknown_end_states(machine),
+                StateMachine::Mealy(machine) => unknown_end_states(machine),
+            };
+            end_states
+                .into_iter()
+                .map(|end_state| {
+                    let message = format!("The end state {} is not a state.", end_state);
+                    (message, end_state.clone())
+                })
+                .collect()
+        },
+    },
+    Rule {
+        id: "unknown-state",
+        severity: Severity::Error,
+        description: "The start state, the transitions, and the outputs must refer to states.",
+        check: unknown_states,
+    },
+    Rule {
+        id: "unknown-symbol",
+        severity: Severity::Error,
+        description: "The read and output symbols must be in the input and output alphabets.",
+        check: unknown_symbols,
+    },
+    Rule {
+        id: "variable-values",
+        severity: Severity::Error,
+        description: "The initial values of the variables must fit their types.",
+        check: |state_machine| match state_machine {
+            StateMachine::Moore(machine) => invalid_initial_values(machine),
+            StateMachine::Mealy(machine) => invalid_initial_values(machine),
+        },
+    },
+    Rule {
+        id: "unreachable-state",
+        severity: Severity::Warning,
+        description: "Every state should be reachable from the start state.",
+        check: unreachable_states,
+    },
+];
+
+/// Run all rules on a Moore or Mealy machine and collect all findings.
+/// Unlike the `validate_*` functions, it does not stop at the first problem.
+///
+/// # Arguments
+///
+/// * `state_machine` - The State Machine to check.
+///
+/// # Returns
+///
+/// * The findings in the order of `RULES`. The machine is valid if none is an error.
+pub fn find_all(state_machine: &StateMachine) -> Vec<Finding> {
+    RULES
+        .iter()
+        .flat_map(|rule| {
+            (rule.check)(state_machine)
+                .into_iter()
+                .map(|(message, element)| Finding {
+                    rule: rule.id,
+                    severity: rule.severity,
+                    message,
+                    element: Some(element),
+                })
+        })
+        .collect()
+}
+
+/// Returns the start state, the states of the transitions, and the states of the Moore
+/// outputs that are not states.
+fn unknown_states(state_machine: &StateMachine) -> Vec<(String, String)> {
+    let model = Model::new("", state_machine);
+    let is_state = |name: &String| model.states.iter().any(|state| state.name == *name);
+    let mut unknown = Vec::new();
+
+    if !is_state(&model.start_state) {
+        let message = format!("The start state {} is not a state.", model.start_state);
+        unknown.push((message, model.start_state.clone()));
+    }
+    for transition in &model.transitions {
+        for state in [&transition.from, &transition.to] {
+            if !is_state(state) {
+                let message = format!(
+                    "The transition from {} to {} refers to {}, which is not a state.",
+                    transition.from, transition.to, state
+                );
+                unknown.push((message, state.clone()));
             }
-        } else {
-            return Err(format!("The state {} is empty.", state));
         }
     }
-
-    for symbol in &machine.input_alphabet {
-        if let Some(first_char) = symbol.chars().next() {
-            if first_char.is_numeric() {
-                return Err(format!("The input {} starts with a number.", symbol));
+    if let StateMachine::Moore(machine) = state_machine {
+        for output in &machine.output_function {
+            if !is_state(&output.current_state) {
+                let message = format!(
+                    "The outputs are set for {}, which is not a state.",
+                    output.current_state
+                );
+                unknown.push((message, output.current_state.clone()));
             }
-        } else {
-            return Err(format!("The input {} is empty.", symbol));
         }
     }
 
-    for symbol in &machine.output_alphabet {
-        if let Some(first_char) = symbol.chars().next() {
-            if first_char.is_numeric() {
-                return Err(format!("The output {} starts with a number.", symbol));
+    unknown
+}
+
+/// Returns the read symbols that are not in the input alphabet and the output symbols
+/// that are not in the output alphabet.
+fn unknown_symbols(state_machine: &StateMachine) -> Vec<(String, String)> {
+    let model = Model::new("", state_machine);
+    let mut unknown = Vec::new();
+
+    for transition in &model.transitions {
+        for guard in &transition.guard {
+            if !model.inputs.contains(&guard.symbol) {
+                let message = format!(
+                    "The transition from {} to {} reads {}, which is not in the input alphabet.",
+                    transition.from, transition.to, guard.symbol
+                );
+                unknown.push((message, guard.symbol.clone()));
+            }
+        }
+        for output in &transition.outputs {
+            if !model.outputs.contains(output) {
+                let message = format!(
+                    "The transition from {} to {} outputs {}, which is not in the output alphabet.",
+                    transition.from, transition.to, output
+                );
+                unknown.push((message, output.clone()));
+            }
+        }
+    }
+    for state in &model.states {
+        for output in &state.outputs {
+            if !model.outputs.contains(output) {
+                let message = format!(
+                    "The state {} outputs {}, which is not in the output alphabet.",
+                    state.name, output
+                );
+                unknown.push((message, output.clone()));
             }
-        } else {
-            return Err(format!("The output {} is empty.", symbol));
         }
     }
 
-    // TODO check if strings do not contain spaces
+    unknown
+}
 
-    Ok(())
+/// Returns the variables whose initial value does not fit their type: a `bool` needs a
+/// boolean, an `int` an integer in the range of `int32_t`, a `uint` an integer in the
+/// range of `uint32_t`, and a `float` a finite number in the range of `float`. These are
+/// the types of the generated code.
+fn invalid_initial_values<T, U>(machine: &Machine<T, U>) -> Vec<(String, String)> {
+    machine
+        .variables
+        .iter()
+        .filter_map(|variable| {
+            let initial = variable.initial?;
+            let fits = match (variable.data_type, initial) {
+                (VariableType::Bool, VariableValue::Bool(_)) => true,
+                (VariableType::Int, VariableValue::Int(i)) => i32::try_from(i).is_ok(),
+                (VariableType::Uint, VariableValue::Int(i)) => u32::try_from(i).is_ok(),
+                (VariableType::Float, VariableValue::Int(i)) => (i as f64).abs() <= f32::MAX as f64,
+                (VariableType::Float, VariableValue::Float(x)) => x.abs() <= f32::MAX as f64,
+                _ => false,
+            };
+            let message = match (variable.data_type, initial) {
+                (VariableType::Float, VariableValue::Float(x)) if !x.is_finite() => format!(
+                    "The initial value {} of the variable {} is not a finite number.",
+                    initial, variable.name
+                ),
+                (data_type, _) => format!(
+                    "The initial value {} of the variable {} does not fit its type {}.",
+                    initial,
+                    variable.name,
+                    format!("{:?}", data_type).to_lowercase()
+                ),
+            };
+            (!fits).then(|| (message, variable.name.clone()))
+        })
+        .collect()
+}
+
+/// Returns the states that cannot be reached from the start state.
+fn unreachable_states(state_machine: &StateMachine) -> Vec<(String, String)> {
+    let model = Model::new("", state_machine);
+
+    let mut reached = vec![&model.start_state];
+    let mut index = 0;
+    while let Some(state) = reached.get(index) {
+        for transition in model.transitions.iter().filter(|t| t.from == **state) {
+            if !reached.contains(&&transition.to) {
+                reached.push(&transition.to);
+            }
+        }
+        index += 1;
+    }
+
+    model
+        .states
+        .iter()
+        .filter(|state| !reached.contains(&&state.name))
+        .map(|state| {
+            let message = format!(
+                "The state {} cannot be reached from the start state.",
+                state.name
+            );
+            (message, state.name.clone())
+        })
+        .collect()
 }
 
 #[cfg(test)]
 mod test {
-    use crate::state_machines::MooreMachine;
+    use crate::state_machines::{MooreMachine, Variable};
 
     use super::*;
 
     fn create_moore_machine() -> MooreMachine {
         let yaml_str = include_str!("../resources/test_moore.yaml");
-        let moore_machine = serde_yaml::from_str(&yaml_str);
+        let moore_machine = serde_yaml::from_str(yaml_str);
         moore_machine.unwrap()
     }
 
     #[test]
     fn test_validate_unique_elements() {
-        let machine = &create_moore_machine();
+        let machine = create_moore_machine();
 
         assert!(validate_unique_elements(&machine).is_ok());
     }
@@ -164,7 +559,7 @@ mod test {
 
     #[test]
     fn test_validate_end_states() {
-        let machine = &create_moore_machine();
+        let machine = create_moore_machine();
 
         assert!(validate_end_states(&machine).is_ok());
     }
@@ -180,7 +575,7 @@ mod test {
 
     #[test]
     fn test_validate_legal_variable_name() {
-        let machine = &create_moore_machine();
+        let machine = create_moore_machine();
 
         assert!(validate_legal_variable_name(&machine).is_ok());
     }
@@ -208,4 +603,237 @@ mod test {
 
         assert!(validate_legal_variable_name(&machine).is_err());
     }
+
+    #[test]
+    fn test_validate_unique_elements_duplicate_variable() {
+        let mut machine = create_moore_machine();
+        machine.variables = vec![Variable {
+            name: "q1".to_string(),
+            data_type: VariableType::Uint,
+            initial: None,
+        }];
+
+        assert!(validate_unique_elements(&machine).is_err());
+    }
+
+    #[test]
+    fn test_find_all() {
+        let machine = crate::test_support::parse(include_str!("../resources/test_moore.yaml"));
+
+        assert!(find_all(&machine).is_empty());
+    }
+
+    #[test]
+    fn test_validate_machine_name() {
+        assert!(validate_machine_name("motor_sm").is_ok());
+        assert!(validate_machine_name("_motor2").is_ok());
+        assert_eq!(
+            validate_machine_name("motor-sm").unwrap_err(),
+            "The name \"motor-sm\" is not an identifier."
+        );
+        assert!(validate_machine_name("").is_err());
+        assert!(validate_machine_name("2motor").is_err());
+        assert!(validate_machine_name("motör").is_err());
+    }
+
+    #[test]
+    fn test_name_collisions() {
+        let mut machine = create_moore_machine();
+        machine.states = vec!["q_1".into(), "q1".into(), "Q1".into(), "idle".into()];
+        machine.input_alphabet = vec!["startMotor".into(), "start_motor".into()];
+
+        let collisions = name_collisions(&machine);
+
+        assert_eq!(
+            collisions,
+            [
+                (
+                    "The names q_1 and q1 are the same in the generated code.".to_string(),
+                    "q1".to_string()
+                ),
+                (
+                    "The names q_1 and Q1 are the same in the generated code.".to_string(),
+                    "Q1".to_string()
+                ),
+                (
+                    "The names startMotor and start_motor are the same in the generated code."
+                        .to_string(),
+                    "start_motor".to_string()
+                ),
+            ]
+        );
+        assert!(validate_state_machine(&StateMachine::Moore(machine)).is_err());
+        assert!(name_collisions(&create_moore_machine()).is_empty());
+
+        let mut machine = create_moore_machine();
+        machine.variables = ["previousState", "count", "output"]
+            .map(|name| Variable {
+                name: name.to_string(),
+                data_type: VariableType::Bool,
+                initial: None,
+            })
+            .into();
+        let names: Vec<_> = name_collisions(&machine)
+            .into_iter()
+            .map(|(_, name)| name)
+            .collect();
+        assert_eq!(names, ["previousState", "output"]);
+    }
+
+    #[test]
+    fn test_find_all_collects_every_finding() {
+        let mut machine = create_moore_machine();
+        machine.states = vec![
+            "1".into(),
+            "q2".into(),
+            "q3".into(),
+            "q4".into(),
+            "q2".into(),
+        ];
+        machine.end_states = vec!["q3".into(), "q5".into()];
+        machine.transitions[0].new_state = "q9".into();
+        machine.transitions[1].read_symbol = vec!["!zzz".into()];
+        machine.output_function[0].output_symbol.push("o7".into());
+
+        let findings = find_all(&StateMachine::Moore(machine));
+        let rules: Vec<_> = findings.iter().map(|f| f.rule).collect();
+
+        assert_eq!(
+            rules,
+            [
+                "legal-names",
+                "unique-elements",
+                "end-states",
+                "unknown-state",
+                "unknown-state",
+                "unknown-state",
+                "unknown-state",
+                "unknown-symbol",
+                "unreachable-state",
+                "unreachable-state",
+                "unreachable-state",
+                "unreachable-state",
+                "unreachable-state",
+            ]
+        );
+        assert_eq!(findings[0].element.as_deref(), Some("1"));
+        assert_eq!(findings[1].element.as_deref(), Some("q2"));
+        assert_eq!(findings[2].message, "The end state q5 is not a state.");
+        assert_eq!(findings[3].message, "The start state q1 is not a state.");
+        assert_eq!(
+            findings[6].message,
+            "The outputs are set for q1, which is not a state."
+        );
+        assert_eq!(findings[7].element.as_deref(), Some("zzz"));
+        assert_eq!(findings[12].severity, Severity::Warning);
+    }
+
+    #[test]
+    fn test_validate_state_machine_unknown_symbols() {
+        let machine = crate::test_support::parse(
+            &include_str!("../resources/test_mealy.yaml").replace("\"i1\"]", "\"zzz\"]"),
+        );
+
+        assert_eq!(
+            validate_state_machine(&machine).unwrap_err(),
+            "The transition from q2 to q3 reads zzz, which is not in the input alphabet."
+        );
+
+        let machine = crate::test_support::parse(
+            &include_str!("../resources/test_mealy.yaml")
+                .replace("output_symbol: \"o1\"", "output_symbol: \"o9\""),
+        );
+        let findings = find_all(&machine);
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].rule, "unknown-symbol");
+        assert_eq!(findings[0].element.as_deref(), Some("o9"));
+    }
+
+    #[test]
+    fn test_validate_state_machine_ignores_warnings() {
+        let machine = crate::test_support::parse(
+            &include_str!("../resources/test_moore.yaml")
+                .replace("  - \"q3\"\ninput", "  - \"q3\"\n  - \"q4\"\ninput"),
+        );
+
+        assert_eq!(find_all(&machine)[0].severity, Severity::Warning);
+        assert!(validate_state_machine(&machine).is_ok());
+    }
+
+    #[test]
+    fn test_invalid_initial_values() {
+        let variable = |data_type, initial| Variable {
+            name: "v".to_string(),
+            data_type,
+            initial: Some(initial),
+        };
+        let mut machine = create_moore_machine();
+        machine.variables = vec![
+            variable(VariableType::Uint, VariableValue::Int(3)),
+            variable(VariableType::Float, VariableValue::Int(3)),
+            variable(VariableType::Float, VariableValue::Float(0.5)),
+            variable(VariableType::Bool, VariableValue::Bool(true)),
+            variable(VariableType::Int, VariableValue::Int(i32::MIN.into())),
+            variable(VariableType::Uint, VariableValue::Int(u32::MAX.into())),
+            variable(VariableType::Float, VariableValue::Float(-3.0e38)),
+        ];
+        assert!(invalid_initial_values(&machine).is_empty());
+
+        machine.variables = vec![
+            variable(VariableType::Uint, VariableValue::Int(-3)),
+            variable(VariableType::Int, VariableValue::Float(1.5)),
+            variable(VariableType::Bool, VariableValue::Int(0)),
+            variable(VariableType::Float, VariableValue::Float(f64::NAN)),
+            variable(VariableType::Float, VariableValue::Float(f64::INFINITY)),
+            variable(VariableType::Int, VariableValue::Int(1 << 40)),
+            variable(
+                VariableType::Int,
+                VariableValue::Int(i64::from(i32::MAX) + 1),
+            ),
+            variable(VariableType::Uint, VariableValue::Int(1 << 32)),
+            variable(VariableType::Float, VariableValue::Float(1.0e39)),
+        ];
+        let messages: Vec<_> = invalid_initial_values(&machine)
+            .into_iter()
+            .map(|(message, _)| message)
+            .collect();
+
+        assert_eq!(
+            messages,
+            [
+                "The initial value -3 of the variable v does not fit its type uint.",
+                "The initial value 1.5 of the variable v does not fit its type int.",
+                "The initial value 0 of the variable v does not fit its type bool.",
+                "The initial value NaN of the variable v is not a finite number.",
+                "The initial value inf of the variable v is not a finite number.",
+                "The initial value 1099511627776 of the variable v does not fit its type int.",
+                "The initial value 2147483648 of the variable v does not fit its type int.",
+                "The initial value 4294967296 of the variable v does not fit its type uint.",
+                "The initial value 1e39 of the variable v does not fit its type float.",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_state_machine_nan_from_yaml() {
+        let yaml_str = format!(
+            "{}variables:\n  - name: gain\n    type: float\n    initial: .nan\n",
+            include_str!("../resources/test_moore.yaml")
+        );
+
+        assert!(validate_state_machine(&crate::test_support::parse(&yaml_str)).is_err());
+    }
+
+    #[test]
+    fn test_validate_legal_variable_name_variable_starts_with_number() {
+        let mut machine = create_moore_machine();
+        machine.variables = vec![Variable {
+            name: "1counter".to_string(),
+            data_type: VariableType::Uint,
+            initial: None,
+        }];
+
+        assert!(validate_legal_variable_name(&machine).is_err());
+    }
 }
@@ -0,0 +1,186 @@
+/*!
+C++ code generator
+
+This module generates a header-only C++17 class from a state machine. The class
+contains the states as `enum class State`, the nested `Input` and `Output` structs,
+and optionally virtual hooks that are called on entering and exiting a state.
+
+The generated code is written to a file or directory.
+*/
+
+use minijinja::{context, Value};
+
+use crate::files::Files;
+use crate::model::Model;
+use crate::state_machines::StateMachine;
+use crate::templates::Templates;
+
+/// The language of the templates in `resources/templates`.
+pub const LANGUAGE: &str = "cpp";
+
+/// Generates the C++ class for the state machine. The code is written to a file or directory.
+/// The name is used for the file name and, in PascalCase, for the class name.
+/// If `hooks` is set, the class gets the virtual functions `onEntry` and `onExit`.
+pub fn generate(
+    name: &str,
+    state_machine: &StateMachine,
+    templates: &Templates,
+    hooks: bool,
+    mut files: Files,
+) -> Result<Files, String> {
+    let model = Model::new(name, state_machine);
+    let context = context! { hooks, ..Value::from_serialize(&model) };
+
+    files.add_file(
+        format!("{}.hpp", name),
+        templates.render("machine.hpp", &context)?,
+    );
+
+    Ok(files)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_support::{parse, run_tool, temp_dir};
+    use std::path::{Path, PathBuf};
+    use std::process::Command;
+
+    /// Generates the C++ class into a unique directory in the temp directory.
+    fn generate_files(name: &str, yaml_str: &str, hooks: bool) -> PathBuf {
+        let dir = temp_dir(&format!("cpp_{}", name));
+        let templates = Templates::new(LANGUAGE, None).unwrap();
+        let files = generate(
+            name,
+            &parse(yaml_str),
+            &templates,
+            hooks,
+            Files::new(dir.clone()),
+        )
+        .unwrap();
+        files.write().unwrap();
+        dir
+    }
+
+    /// Compiles and runs the program as C++17 with all warnings as errors.
+    fn assert_runs(dir: &Path, main: &str) {
+        let source = dir.join("main.cpp");
+        let executable = dir.join("main");
+        std::fs::write(&source, main).unwrap();
+
+        let args = ["-std=c++17", "-Wall", "-Wextra", "-Werror"];
+        let paths = [source.to_str().unwrap(), "-o", executable.to_str().unwrap()];
+        run_tool("CXX", "c++", &[&args[..], &paths[..]].concat());
+
+        let status = Command::new(&executable).status().unwrap();
+        assert_eq!(status.code(), Some(0));
+    }
+
+    #[test]
+    fn test_moore_class() {
+        let dir = generate_files("motor", include_str!("../resources/test_moore.yaml"), false);
+
+        assert_runs(
+            &dir,
+            r#"#include "motor.hpp"
+
+int main()
+{
+    Motor motor;
+    Motor::Input input;
+    input.i0 = true;
+    input.i1 = true;
+
+    if (!motor.step(input).o5 || motor.state() != Motor::State::q2)
+        return 1;
+    if (motor.previousState() != Motor::State::q1)
+        return 2;
+    if (motor.step(input).o5 || !motor.isEndState())
+        return 3;
+
+    motor.reset();
+    return motor.state() == Motor::State::q1 ? 0 : 4;
+}
+"#,
+        );
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_mealy_class_with_hooks() {
+        let dir = generate_files(
+            "pairing",
+            include_str!("../resources/test_mealy.yaml"),
+            true,
+        );
+
+        assert_runs(
+            &dir,
+            r#"#include "pairing.hpp"
+
+class CountingPairing : public Pairing
+{
+public:
+    int entries = 0;
+    int exits = 0;
+
+protected:
+    void onEntry(State) override { entries++; }
+    void onExit(State) override { exits++; }
+};
+
+int main()
+{
+    CountingPairing pairing;
+    Pairing::Input input;
+    input.i0 = true;
+
+    if (!pairing.step(input).o0 || pairing.state() != Pairing::State::q2)
+        return 1;
+    if (pairing.step(input).o0 || pairing.state() != Pairing::State::q2)
+        return 2;
+    return pairing.entries == 1 && pairing.exits == 1 ? 0 : 3;
+}
+"#,
+        );
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_variables_and_no_guards() {
+        let yaml_str = "
+states: [idle, running]
+input_alphabet: []
+transitions:
+  - current_state: idle
+    read_symbol: []
+    new_state: running
+output_function: []
+start_state: idle
+end_states: []
+output_alphabet: []
+variables:
+  - name: counter
+    type: uint
+    initial: 3
+";
+        let dir = generate_files("minimal", yaml_str, false);
+
+        assert_runs(
+            &dir,
+            r#"#include "minimal.hpp"
+
+int main()
+{
+    Minimal minimal;
+    minimal.step({});
+    return minimal.state() == Minimal::State::running && minimal.counter == 3 ? 0 : 1;
+}
+"#,
+        );
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,263 @@
+/*!
+Semantic diff of two state machines
+
+This module compares two machines by their meaning instead of their text, so that
+a machine compares equal to itself in another format or layout. Each change is one
+line: `+` for added and `-` for removed elements, `~` for changed values. The
+order of the states and symbols is compared as well, since it decides the state
+encoding and the layout of the generated code. The transitions are compared per
+state and in order, because the first enabled transition fires.
+*/
+
+use crate::model::{Model, Transition};
+use crate::state_machines::StateMachine;
+
+/// Returns the changes from `old` to `new`. The machines are equal if there are none.
+pub fn diff(old: &StateMachine, new: &StateMachine) -> Vec<String> {
+    let old = Model::new("", old);
+    let new = Model::new("", new);
+    let mut changes = Vec::new();
+
+    if old.kind != new.kind {
+        changes.push(format!("~ type: {:?} -> {:?}", old.kind, new.kind).to_lowercase());
+    }
+    let states = |model: &Model| {
+        model
+            .states
+            .iter()
+            .map(|s| s.name.clone())
+            .collect::<Vec<_>>()
+    };
+    compare_lists(&mut changes, "state", &states(&old), &states(&new));
+    compare_order(&mut changes, "states", &states(&old), &states(&new));
+    compare_lists(&mut changes, "input", &old.inputs, &new.inputs);
+    compare_order(&mut changes, "inputs", &old.inputs, &new.inputs);
+    compare_lists(&mut changes, "output", &old.outputs, &new.outputs);
+    compare_order(&mut changes, "outputs", &old.outputs, &new.outputs);
+    if old.start_state != new.start_state {
+        changes.push(format!(
+            "~ start_state: {} -> {}",
+            old.start_state, new.start_state
+        ));
+    }
+    compare_lists(&mut changes, "end_state", &old.end_states, &new.end_states);
+
+    for state in &new.states {
+        let Some(old_state) = old.states.iter().find(|s| s.name == state.name) else {
+            continue;
+        };
+        if old_state.outputs != state.outputs {
+            changes.push(format!(
+                "~ outputs of {}: [{}] -> [{}]",
+                state.name,
+                old_state.outputs.join(", "),
+                state.outputs.join(", ")
+            ));
+        }
+    }
+
+    // The first enabled transition of a state fires, so the transitions are compared per
+    // state and in order.
+    let transitions = |model: &Model, name: &str| {
+        model
+            .states
+            .iter()
+            .filter(|s| s.name == name)
+            .flat_map(|s| s.transitions.iter().map(describe))
+            .collect::<Vec<_>>()
+    };
+    let old_names = states(&old);
+    let new_names = states(&new);
+    let names = old_names
+        .iter()
+        .chain(new_names.iter().filter(|n| !old_names.contains(n)));
+    for name in names {
+        let old_transitions = transitions(&old, name);
+        let new_transitions = transitions(&new, name);
+        compare_lists(
+            &mut changes,
+            "transition",
+            &old_transitions,
+            &new_transitions,
+        );
+        compare_order(
+            &mut changes,
+            &format!("transitions of {}", name),
+            &old_transitions,
+            &new_transitions,
+        );
+    }
+
+    let variables = |model: &Model| {
+        model
+            .variables
+            .iter()
+            .map(|v| {
+                let data_type = format!("{:?}", v.data_type).to_lowercase();
+                format!("{}: {} = {}", v.name, data_type, v.initial)
+            })
+            .collect::<Vec<_>>()
+    };
+    compare_lists(&mut changes, "variable", &variables(&old), &variables(&new));
+
+    changes
+}
+
+/// Adds the elements that are only in `old` as removed, and the elements that are only
+/// in `new` as added. Duplicates are counted.
+fn compare_lists(changes: &mut Vec<String>, what: &str, old: &[String], new: &[String]) {
+    let mut added = new.to_vec();
+    for element in old {
+        match added.iter().position(|e| e == element) {
+            Some(index) => {
+                added.remove(index);
+            }
+            None => changes.push(format!("- {} {}", what, element)),
+        }
+    }
+    for element in added {
+        changes.push(format!("+ {} {}", what, element));
+    }
+}
+
+/// Adds a change if the elements that are in both lists are in a different order.
+fn compare_order(changes: &mut Vec<String>, what: &str, old: &[String], new: &[String]) {
+    let old_order = common(old, new);
+    let new_order = common(new, old);
+    if old_order != new_order {
+        changes.push(format!(
+            "~ order of the {}: [{}] -> [{}]",
+            what,
+            old_order.join("; "),
+            new_order.join("; ")
+        ));
+    }
+}
+
+/// Returns the elements of `list` that are also in `other`, in the order of `list`.
+/// Duplicates are counted.
+fn common(list: &[String], other: &[String]) -> Vec<String> {
+    let mut other = other.to_vec();
+    list.iter()
+        .filter(|element| match other.iter().position(|e| e == *element) {
+            Some(index) => {
+                other.remove(index);
+                true
+            }
+            None => false,
+        })
+        .cloned()
+        .collect()
+}
+
+/// Returns a transition as `from --[guard]--> to / outputs`.
+fn describe(transition: &Transition) -> String {
+    let guard = transition
+        .guard
+        .iter()
+        .map(|g| format!("{}{}", if g.negated { "!" } else { "" }, g.symbol))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let mut text = format!("{} --[{}]--> {}", transition.from, guard, transition.to);
+    if !transition.outputs.is_empty() {
+        text += &format!(" / {}", transition.outputs.join(", "));
+    }
+    text
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::input::{self, Format};
+    use crate::test_support::parse;
+    use crate::writer;
+
+    #[test]
+    fn test_diff_equal() {
+        let machine = parse(include_str!("../resources/test_moore.yaml"));
+        let json =
+            input::parse(include_str!("../resources/test_moore.json"), Format::Json).unwrap();
+        let formatted = parse(&writer::to_yaml(&machine));
+
+        assert!(diff(&machine, &formatted).is_empty());
+        assert!(diff(&machine, &json).is_empty());
+    }
+
+    #[test]
+    fn test_diff_changes() {
+        let old = parse(include_str!("../resources/test_moore.yaml"));
+        let new = parse(
+            &include_str!("../resources/test_moore.yaml")
+                .replace("read_symbol: [\"i1\"]", "read_symbol: [\"!i0\"]")
+                .replace("[\"o5\", \"o6\"]", "[\"o5\"]")
+                .replace("  - \"q3\"\ninput", "  - \"q3\"\n  - \"q4\"\ninput"),
+        );
+
+        assert_eq!(
+            diff(&old, &new),
+            [
+                "+ state q4",
+                "~ outputs of q1: [o5, o6] -> [o5]",
+                "- transition q2 --[i1]--> q3",
+                "+ transition q2 --[!i0]--> q3",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_transition_order() {
+        let machine = |transitions: &str| {
+            parse(&format!(
+                "states: [q1, q2]
+input_alphabet: [i0, i1]
+output_alphabet: []
+transitions:
+{}output_function: []
+start_state: q1
+end_states: []
+",
+                transitions
+            ))
+        };
+        let a = "  - {current_state: q1, read_symbol: [i0], new_state: q2}\n";
+        let b = "  - {current_state: q1, read_symbol: [i1], new_state: q1}\n";
+        let c = "  - {current_state: q2, read_symbol: [i0], new_state: q1}\n";
+        let old = machine(&[a, b, c].concat());
+
+        assert_eq!(
+            diff(&old, &machine(&[b, c, a].concat())),
+            ["~ order of the transitions of q1: \
+              [q1 --[i0]--> q2; q1 --[i1]--> q1] -> [q1 --[i1]--> q1; q1 --[i0]--> q2]"]
+        );
+        // The transitions of different states can be listed in any order.
+        assert!(diff(&old, &machine(&[c, a, b].concat())).is_empty());
+    }
+
+    #[test]
+    fn test_diff_state_order() {
+        let old = parse(include_str!("../resources/test_moore.yaml"));
+        let new = parse(
+            &include_str!("../resources/test_moore.yaml")
+                .replace("  - \"q1\"\n  - \"q2\"", "  - \"q2\"\n  - \"q1\"")
+                .replace("  - \"o5\"\n  - \"o6\"", "  - \"o6\"\n  - \"o5\""),
+        );
+
+        assert_eq!(
+            diff(&old, &new),
+            [
+                "~ order of the states: [q1; q2; q3] -> [q2; q1; q3]",
+                "~ order of the outputs: [o5; o6] -> [o6; o5]",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_kind() {
+        let moore = parse(include_str!("../resources/test_moore.yaml"));
+        let mealy = parse(include_str!("../resources/test_mealy.yaml"));
+        let changes = diff(&moore, &mealy);
+
+        assert_eq!(changes[0], "~ type: moore -> mealy");
+        assert!(changes.contains(&"+ transition q1 --[i0, !i1]--> q2 / o0".to_string()));
+    }
+}
@@ -0,0 +1,237 @@
+/*!
+Runtime interpreter for state machines
+
+This module executes a Moore or Mealy machine that was loaded at runtime, without
+generating code. The semantics are those of the generated code: the first enabled
+transition of the current state fires, a Moore machine outputs the symbols of the
+state it is in before the step, and a Mealy machine the symbol of the fired transition.
+*/
+
+use std::collections::HashMap;
+
+use crate::model::{Kind, Model, Transition};
+use crate::state_machines::StateMachine;
+
+/// The result of one step.
+#[derive(Debug, Clone)]
+pub struct Step {
+    /// The output symbols that are set in this step.
+    pub outputs: Vec<String>,
+    /// The transition that fired, or `None` if the machine stayed in its state.
+    pub transition: Option<Transition>,
+}
+
+/// Executes a state machine step by step.
+///
+/// ```
+/// use std::collections::HashMap;
+/// use state_machine_builder::executor::Executor;
+/// use state_machine_builder::MachineBuilder;
+///
+/// let machine = MachineBuilder::new()
+///     .state("idle")
+///     .state("running")
+///     .transition("idle", ["start"], "running")
+///     .state_outputs("running", ["motor_on"])
+///     .build()
+///     .unwrap();
+///
+/// let mut executor = Executor::new(&machine).unwrap();
+/// let step = executor.step(&HashMap::from([("start".to_string(), true)])).unwrap();
+/// assert_eq!(step.transition.unwrap().to, "running");
+/// assert_eq!(executor.step_bits(0).unwrap().outputs, ["motor_on"]);
+/// ```
+#[derive(Debug)]
+pub struct Executor {
+    model: Model,
+    state: usize,
+    previous_state: usize,
+}
+
+impl Executor {
+    /// Creates an executor in the start state. The start state and the targets of all
+    /// transitions must be states of the machine.
+    pub fn new(state_machine: &StateMachine) -> Result<Self, String> {
+        let model = Model::new("", state_machine);
+
+        let index = |name: &str| model.states.iter().position(|s| s.name == name);
+        let start = index(&model.start_state)
+            .ok_or_else(|| format!("The start state {} is not a state.", model.start_state))?;
+        if let Some(transition) = model.transitions.iter().find(|t| index(&t.to).is_none()) {
+            return Err(format!(
+                "The transition from {} goes to {}, which is not a state.",
+                transition.from, transition.to
+            ));
+        }
+
+        Ok(Executor {
+            model,
+            state: start,
+            previous_state: start,
+        })
+    }
+
+    /// Executes one step. Input symbols that are missing from the map are not set.
+    /// Returns an error for symbols that are not in the input alphabet.
+    pub fn step(&mut self, input: &HashMap<String, bool>) -> Result<Step, String> {
+        if let Some(symbol) = input.keys().find(|s| !self.model.inputs.contains(s)) {
+            return Err(format!(
+                "The input {} is not in the input alphabet.",
+                symbol
+            ));
+        }
+        let transition = self.next(|symbol| input.get(symbol).copied().unwrap_or(false));
+        Ok(self.fire(transition))
+    }
+
+    /// Executes one step. Bit `i` of `input` is the `i`-th symbol of the input alphabet.
+    /// Returns an error for bits outside of the input alphabet.
+    pub fn step_bits(&mut self, input: u64) -> Result<Step, String> {
+        let width = self.model.inputs.len();
+        if width > 64 {
+            return Err(format!(
+                "The machine has {} input symbols, but a bitset holds at most 64.",
+                width
+            ));
+        }
+        if width < 64 && input >> width != 0 {
+            return Err(format!(
+                "The input {:#x} has bits outside of the {} input symbols.",
+                input, width
+            ));
+        }
+
+        let transition = self.next(|symbol| {
+            let index = self.model.inputs.iter().position(|s| s == symbol);
+            index.is_some_and(|i| input >> i & 1 == 1)
+        });
+        Ok(self.fire(transition))
+    }
+
+    /// Returns the current state.
+    pub fn state(&self) -> &str {
+        &self.model.states[self.state].name
+    }
+
+    /// Returns the state before the last step.
+    pub fn previous_state(&self) -> &str {
+        &self.model.states[self.previous_state].name
+    }
+
+    /// Returns whether the machine is in an end state.
+    pub fn is_end_state(&self) -> bool {
+        self.model.states[self.state].is_end
+    }
+
+    /// Returns the input alphabet in the order of the bits of `step_bits`.
+    pub fn inputs(&self) -> &[String] {
+        &self.model.inputs
+    }
+
+    /// Returns the executor to the start state.
+    pub fn reset(&mut self) {
+        let start = self.model.states.iter().position(|s| s.is_start).unwrap();
+        self.state = start;
+        self.previous_state = start;
+    }
+
+    /// Returns the transition of the current state that fires for the input.
+    fn next(&self, input: impl Fn(&str) -> bool) -> Option<Transition> {
+        self.model.states[self.state].next(input).cloned()
+    }
+
+    /// Moves to the target of the transition and returns the outputs of the step.
+    fn fire(&mut self, transition: Option<Transition>) -> Step {
+        let outputs = match self.model.kind {
+            Kind::Moore => self.model.states[self.state].outputs.clone(),
+            Kind::Mealy => transition
+                .as_ref()
+                .map(|t| t.outputs.clone())
+                .unwrap_or_default(),
+        };
+
+        self.previous_state = self.state;
+        if let Some(transition) = &transition {
+            self.state = self
+                .model
+                .states
+                .iter()
+                .position(|s| s.name == transition.to)
+                .unwrap();
+        }
+
+        Step {
+            outputs,
+            transition,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_support::parse;
+
+    fn input(symbols: &[(&str, bool)]) -> HashMap<String, bool> {
+        symbols.iter().map(|(s, v)| (s.to_string(), *v)).collect()
+    }
+
+    #[test]
+    fn test_step_moore() {
+        let machine = parse(include_str!("../resources/test_moore.yaml"));
+        let mut executor = Executor::new(&machine).unwrap();
+
+        let step = executor.step(&input(&[("i0", true)])).unwrap();
+        assert_eq!(step.outputs, ["o5", "o6"]);
+        assert!(step.transition.is_none());
+        assert_eq!(executor.state(), "q1");
+
+        let step = executor
+            .step(&input(&[("i0", true), ("i1", true)]))
+            .unwrap();
+        assert_eq!(step.outputs, ["o5", "o6"]);
+        assert_eq!(step.transition.unwrap().to, "q2");
+        assert_eq!(executor.previous_state(), "q1");
+
+        let step = executor.step_bits(0b10).unwrap();
+        assert!(step.outputs.is_empty());
+        assert_eq!(executor.state(), "q3");
+        assert!(executor.is_end_state());
+
+        executor.reset();
+        assert_eq!(executor.state(), "q1");
+    }
+
+    #[test]
+    fn test_step_mealy() {
+        let machine = parse(include_str!("../resources/test_mealy.yaml"));
+        let mut executor = Executor::new(&machine).unwrap();
+        assert_eq!(executor.inputs(), ["i0", "i1"]);
+
+        let step = executor.step_bits(0b01).unwrap();
+        assert_eq!(step.outputs, ["o0"]);
+        assert_eq!(executor.state(), "q2");
+
+        // The first enabled transition fires.
+        let step = executor.step(&input(&[("i1", true)])).unwrap();
+        assert_eq!(step.outputs, ["o1"]);
+        assert_eq!(executor.state(), "q3");
+
+        let step = executor.step(&input(&[])).unwrap();
+        assert!(step.outputs.is_empty() && step.transition.is_none());
+    }
+
+    #[test]
+    fn test_step_errors() {
+        let machine = parse(include_str!("../resources/test_mealy.yaml"));
+        let mut executor = Executor::new(&machine).unwrap();
+
+        assert!(executor.step(&input(&[("i2", true)])).is_err());
+        assert!(executor.step_bits(0b100).is_err());
+        assert_eq!(executor.state(), "q1");
+
+        let yaml_str = include_str!("../resources/test_mealy.yaml")
+            .replace("new_state: \"q3\"", "new_state: \"q4\"");
+        assert!(Executor::new(&parse(&yaml_str)).is_err());
+    }
+}
@@ -1,5 +1,10 @@
 use std::path::PathBuf;
 
+use similar::TextDiff;
+
+/// The number of unchanged lines around the changes in `Files::diff`.
+const CONTEXT_LINES: usize = 3;
+
 /// A collection of files. Contains the path to the directory and the files.
 #[derive(Debug)]
 pub struct Files {
@@ -28,18 +33,173 @@ impl Files {
         self.files.push(File { name, content });
     }
 
-    /// Writes the files to the directory. Returns a message.
+    /// Returns the names and contents of the files.
+    pub fn contents(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.files
+            .iter()
+            .map(|file| (file.name.as_str(), file.content.as_str()))
+    }
+
+    /// Returns the names of the files that are missing in the directory or differ from it.
+    pub fn stale(&self) -> Vec<&str> {
+        self.files
+            .iter()
+            .filter(|file| self.read(file).as_deref() != Some(file.content.as_str()))
+            .map(|file| file.name.as_str())
+            .collect()
+    }
+
+    /// Returns the changes that `write` would make as a unified diff. Missing files are
+    /// compared to an empty file.
+    pub fn diff(&self) -> String {
+        let mut diff = String::new();
+        for file in &self.files {
+            let old = self.read(file).unwrap_or_default();
+            if old != file.content {
+                let path = self.path.join(&file.name);
+                diff += &format!("--- {}\n+++ {}\n", path.display(), path.display());
+                diff += &unified_diff(&old, &file.content);
+            }
+        }
+        diff
+    }
+
+    /// Writes the files that changed to the directory. Unchanged files are not touched,
+    /// so that build tools do not rebuild them. Each file is written to a temporary file
+    /// first and then renamed, so that readers never see a partly written file.
+    /// Returns a message.
     pub fn write(&self) -> Result<String, std::io::Error> {
         std::fs::create_dir_all(&self.path)?;
 
-        for file in &self.files {
+        let stale = self.stale();
+        for file in self
+            .files
+            .iter()
+            .filter(|f| stale.contains(&f.name.as_str()))
+        {
             let path = self.path.join(&file.name);
-            std::fs::write(path, &file.content)?;
+            let temp = self
+                .path
+                .join(format!(".{}.{}.tmp", file.name, std::process::id()));
+            std::fs::write(&temp, &file.content)?;
+            if let Err(e) = std::fs::rename(&temp, &path) {
+                let _ = std::fs::remove_file(&temp);
+                return Err(e);
+            }
         }
 
-        Ok(format!(
-            "The files have been written to {}.",
-            self.path.to_str().unwrap()
-        ))
+        let path = self.path.display();
+        Ok(match stale.len() {
+            0 => format!("The files in {} are up to date.", path),
+            n if n == self.files.len() => format!("The files have been written to {}.", path),
+            n => format!(
+                "{} of {} files have been written to {}.",
+                n,
+                self.files.len(),
+                path
+            ),
+        })
+    }
+
+    /// Returns the content of the file in the directory, or `None` if it cannot be read.
+    fn read(&self, file: &File) -> Option<String> {
+        std::fs::read_to_string(self.path.join(&file.name)).ok()
+    }
+}
+
+/// Returns the hunks of a unified diff from `old` to `new`.
+fn unified_diff(old: &str, new: &str) -> String {
+    TextDiff::from_lines(old, new)
+        .unified_diff()
+        .context_radius(CONTEXT_LINES)
+        .to_string()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_support::temp_dir;
+
+    fn files(dir: &std::path::Path, header: &str) -> Files {
+        let mut files = Files::new(dir.to_path_buf());
+        files.add_file("machine.h".into(), header.into());
+        files.add_file("machine.c".into(), "int x;\n".into());
+        files
+    }
+
+    #[test]
+    fn test_write_only_changed_files() {
+        let dir = temp_dir("files_write");
+        let source = dir.join("machine.c");
+
+        assert_eq!(files(&dir, "a\n").stale(), ["machine.h", "machine.c"]);
+        let message = files(&dir, "a\n").write().unwrap();
+        assert_eq!(
+            message,
+            format!("The files have been written to {}.", dir.display())
+        );
+        let modified = std::fs::metadata(&source).unwrap().modified().unwrap();
+
+        assert!(files(&dir, "a\n").stale().is_empty());
+        assert!(files(&dir, "a\n")
+            .write()
+            .unwrap()
+            .ends_with("are up to date."));
+
+        let changed = files(&dir, "b\n");
+        assert_eq!(changed.stale(), ["machine.h"]);
+        assert!(changed.write().unwrap().starts_with("1 of 2 files"));
+        assert_eq!(
+            std::fs::read_to_string(dir.join("machine.h")).unwrap(),
+            "b\n"
+        );
+        assert_eq!(
+            std::fs::metadata(&source).unwrap().modified().unwrap(),
+            modified
+        );
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 2);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_diff() {
+        let dir = temp_dir("files_diff");
+        files(&dir, "1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n")
+            .write()
+            .unwrap();
+        let header = dir.join("machine.h").display().to_string();
+
+        let diff = files(&dir, "1\n2\nthree\n4\n5\n6\n7\n8\n9\n10\neleven\n").diff();
+
+        assert_eq!(
+            diff,
+            format!(
+                "--- {0}\n+++ {0}\n@@ -1,6 +1,6 @@\n 1\n 2\n-3\n+three\n 4\n 5\n 6\n@@ -8,3 +8,4 @@\n 8\n 9\n 10\n+eleven\n",
+                header
+            )
+        );
+        assert!(files(&dir, "1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n")
+            .diff()
+            .is_empty());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_unified_diff_large_file() {
+        // A table of the lengths of all common subsequences would not fit into memory.
+        let old: String = (0..100_000).map(|i| format!("{}\n", i)).collect();
+        let new = old.replace("\n50000\n", "\nfifty thousand\n");
+
+        assert_eq!(
+            unified_diff(&old, &new),
+            "@@ -49998,7 +49998,7 @@\n 49997\n 49998\n 49999\n-50000\n+fifty thousand\n 50001\n 50002\n 50003\n"
+        );
+    }
+
+    #[test]
+    fn test_unified_diff_new_file() {
+        assert_eq!(unified_diff("", "a\nb\n"), "@@ -0,0 +1,2 @@\n+a\n+b\n");
     }
 }
@@ -0,0 +1,286 @@
+/*!
+Go code generator
+
+This module generates a Go package from a state machine. The package contains the
+states as typed `State` constants, the `Input` and `Output` structs, and a `Machine`
+type. A test file with one test per transition is generated next to it.
+
+The generated code is written to a file or directory.
+*/
+
+use minijinja::{context, Value};
+use serde::Serialize;
+
+use crate::files::Files;
+use crate::model::{Kind, Model};
+use crate::state_machines::StateMachine;
+use crate::templates::Templates;
+
+/// The language of the templates in `resources/templates`.
+pub const LANGUAGE: &str = "go";
+
+/// A generated test: one step from `from` with the `inputs` set.
+#[derive(Debug, PartialEq, Serialize)]
+struct TransitionTest {
+    from: String,
+    inputs: Vec<String>,
+    to: String,
+    outputs: Vec<String>,
+}
+
+/// Generates the Go package for the state machine. The code is written to a file or directory.
+/// The name is used for the file and package name.
+pub fn generate(
+    name: &str,
+    state_machine: &StateMachine,
+    templates: &Templates,
+    mut files: Files,
+) -> Result<Files, String> {
+    let model = Model::new(name, state_machine);
+    let context = context! {
+        tests => transition_tests(&model),
+        ..Value::from_serialize(&model)
+    };
+
+    files.add_file(
+        format!("{}.go", name),
+        templates.render("machine.go", &context)?,
+    );
+    files.add_file(
+        format!("{}_test.go", name),
+        templates.render("machine_test.go", &context)?,
+    );
+
+    Ok(files)
+}
+
+/// Creates one test per transition. The input sets exactly the read symbols that are not
+/// negated. The expected state and outputs follow from the transition that actually fires,
+/// which can be an earlier transition of the same state.
+fn transition_tests(model: &Model) -> Vec<TransitionTest> {
+    model
+        .states
+        .iter()
+        .flat_map(|state| {
+            state.transitions.iter().map(move |transition| {
+                let inputs = transition
+                    .guard
+                    .iter()
+                    .filter(|g| !g.negated)
+                    .map(|g| g.symbol.clone())
+                    .collect::<Vec<String>>();
+                let fired = state.next(|s| inputs.iter().any(|i| i == s));
+
+                let to = fired.map_or(&state.name, |t| &t.to).clone();
+                let outputs = match model.kind {
+                    Kind::Moore => state.outputs.clone(),
+                    Kind::Mealy => fired.map(|t| t.outputs.clone()).unwrap_or_default(),
+                };
+
+                TransitionTest {
+                    from: state.name.clone(),
+                    inputs,
+                    to,
+                    outputs,
+                }
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_support::{assert_golden, has_tool, parse, run_tool, temp_dir};
+    use std::path::Path;
+
+    fn render(yaml_str: &str, template: &str) -> String {
+        let model = Model::new("motor_control", &parse(yaml_str));
+        let context = context! {
+            tests => transition_tests(&model),
+            ..Value::from_serialize(&model)
+        };
+        let templates = Templates::new(LANGUAGE, None).unwrap();
+        templates.render(template, &context).unwrap()
+    }
+
+    #[test]
+    fn test_moore_package() {
+        let code = render(include_str!("../resources/test_moore.yaml"), "machine.go");
+
+        assert!(code.starts_with("// Code generated by State Machine Builder. DO NOT EDIT."));
+        assert!(code.contains("package motorcontrol\n"));
+        assert!(code.contains("\tStateQ1 State = iota\n\tStateQ2\n\tStateQ3\n"));
+        assert!(code.contains("type Input struct {\n\tI0 bool\n\tI1 bool\n}"));
+        assert!(code.contains("\tcase StateQ1:\n\t\tm.Output.O5 = true\n\t\tm.Output.O6 = true\n"));
+        assert!(code.contains("\t\tif input.I0 && input.I1 {\n\t\t\tm.State = StateQ2\n\t\t}\n"));
+        assert!(code.contains("\treturn m.State == StateQ3\n}"));
+    }
+
+    #[test]
+    fn test_mealy_else_if() {
+        let code = render(include_str!("../resources/test_mealy.yaml"), "machine.go");
+
+        assert!(code.contains("\t\t} else if !input.I0 {\n\t\t\tm.Output.O0 = true\n"));
+    }
+
+    #[test]
+    fn test_machine_struct_is_aligned() {
+        let yaml_str = format!(
+            "{}variables:\n  - name: retry_counter\n    type: uint\n",
+            include_str!("../resources/test_moore.yaml")
+        );
+        let code = render(&yaml_str, "machine.go");
+
+        assert!(code.contains(
+            "\tState         State\n\tPreviousState State\n\tOutput        Output\n\tRetryCounter  uint32\n"
+        ));
+        assert!(code.contains("\tm.RetryCounter = 0\n"));
+    }
+
+    /// Builds an expected test, so that the expectations do not depend on `transition_tests`.
+    fn expected(from: &str, inputs: &[&str], to: &str, outputs: &[&str]) -> TransitionTest {
+        TransitionTest {
+            from: from.to_string(),
+            inputs: inputs.iter().map(|s| s.to_string()).collect(),
+            to: to.to_string(),
+            outputs: outputs.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_transition_tests() {
+        let moore = Model::new("test", &parse(include_str!("../resources/test_moore.yaml")));
+        let mealy = Model::new("test", &parse(include_str!("../resources/test_mealy.yaml")));
+
+        // Moore outputs belong to the state before the step.
+        assert_eq!(
+            transition_tests(&moore),
+            [
+                expected("q1", &["i0", "i1"], "q2", &["o5", "o6"]),
+                expected("q2", &["i1"], "q3", &[]),
+            ]
+        );
+        // Mealy outputs belong to the transition. Without inputs, q2 -> q3 does not fire,
+        // so the !i0 transition back to q1 does.
+        assert_eq!(
+            transition_tests(&mealy),
+            [
+                expected("q1", &["i0"], "q2", &["o0"]),
+                expected("q2", &["i1"], "q3", &["o1"]),
+                expected("q2", &[], "q1", &["o0"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_shadowed_transition() {
+        // The second transition is never taken with its own input, because the first one fires.
+        let yaml_str = "
+states: [a, b, c]
+input_alphabet: [x, y]
+transitions:
+  - current_state: a
+    read_symbol: [x]
+    new_state: b
+  - current_state: a
+    read_symbol: [x, y]
+    new_state: c
+output_function: []
+start_state: a
+end_states: []
+output_alphabet: []
+";
+        let tests = transition_tests(&Model::new("test", &parse(yaml_str)));
+
+        assert_eq!(tests[1].inputs, vec!["x", "y"]);
+        assert_eq!(tests[1].to, "b");
+    }
+
+    #[test]
+    fn test_test_file() {
+        let code = render(
+            include_str!("../resources/test_moore.yaml"),
+            "machine_test.go",
+        );
+
+        assert!(code.contains("import \"testing\"\n"));
+        assert!(code.contains("func TestTransition0(t *testing.T) {"));
+        assert!(code.contains("output := m.Step(Input{I0: true, I1: true})"));
+        assert!(code.contains("if want := (Output{O5: true, O6: true}); output != want {"));
+        assert!(code.contains("func TestTransition1(t *testing.T) {"));
+    }
+
+    #[test]
+    fn test_minimal_machine() {
+        let yaml_str = "
+states: [idle]
+input_alphabet: []
+transitions: []
+output_function: []
+start_state: idle
+end_states: []
+output_alphabet: []
+";
+        let code = render(yaml_str, "machine.go");
+        let test_code = render(yaml_str, "machine_test.go");
+
+        assert!(code.contains("type Input struct {\n}"));
+        assert!(code.contains("\treturn false\n}"));
+        assert!(!test_code.contains("import"));
+    }
+
+    /// Pins the generated packages, so that changes show up without the Go toolchain.
+    #[test]
+    fn test_golden() {
+        let templates = Templates::new(LANGUAGE, None).unwrap();
+        for (name, yaml_str) in [
+            ("moore", include_str!("../resources/test_moore.yaml")),
+            ("mealy", include_str!("../resources/test_mealy.yaml")),
+        ] {
+            let files = generate(name, &parse(yaml_str), &templates, Files::new("".into()));
+            assert_golden(LANGUAGE, &files.unwrap());
+        }
+    }
+
+    /// Checks that the generated packages are formatted and pass `go vet` and their tests.
+    #[test]
+    fn test_go_compiles() {
+        if !has_tool("GO", "go") {
+            return;
+        }
+        for yaml_str in [
+            include_str!("../resources/test_moore.yaml"),
+            include_str!("../resources/test_mealy.yaml"),
+        ] {
+            let dir = temp_dir("go_compiles");
+            let templates = Templates::new(LANGUAGE, None).unwrap();
+            let files = generate(
+                "motor_control",
+                &parse(yaml_str),
+                &templates,
+                Files::new(dir.clone()),
+            );
+            files.unwrap().write().unwrap();
+            std::fs::write(
+                dir.join("go.mod"),
+                "module example.com/motorcontrol\n\ngo 1.20\n",
+            )
+            .unwrap();
+
+            assert_eq!(run_tool("GOFMT", "gofmt", &[Path::new("-l"), &dir]), "");
+            run_tool(
+                "GO",
+                "go",
+                &[Path::new("-C"), &dir, Path::new("vet"), Path::new("./...")],
+            );
+            run_tool(
+                "GO",
+                "go",
+                &[Path::new("-C"), &dir, Path::new("test"), Path::new("./...")],
+            );
+
+            std::fs::remove_dir_all(dir).unwrap();
+        }
+    }
+}
@@ -0,0 +1,388 @@
+/*!
+Input formats
+
+This module reads a state machine from YAML, JSON, TOML, SCXML, PlantUML or KISS2. The format is
+selected by the file extension. The YAML, JSON and TOML formats share the same
+structure, which is published as a JSON Schema in `resources/state_machine.schema.json`.
+*/
+
+use std::path::Path;
+
+use serde::de::{DeserializeOwned, IgnoredAny};
+use serde::Deserialize;
+
+use serde_json::json;
+
+use crate::kiss;
+use crate::plantuml;
+use crate::scxml;
+use crate::state_machines::{
+    MealyMachine, MealyTransition, MooreMachine, MooreOutputFunction, MooreTransition,
+    StateMachine, Variable,
+};
+
+/// The format of a state machine file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Yaml,
+    Json,
+    Toml,
+    Scxml,
+    PlantUml,
+    Kiss2,
+}
+
+impl Format {
+    /// Returns the format for the extension of the path. Unknown extensions are read as YAML.
+    pub fn from_path(path: &Path) -> Format {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => Format::Json,
+            Some("toml") => Format::Toml,
+            Some("scxml") => Format::Scxml,
+            Some("puml" | "plantuml" | "pu") => Format::PlantUml,
+            Some("kiss2" | "kiss" | "kis") => Format::Kiss2,
+            _ => Format::Yaml,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Format::Yaml => "YAML",
+            Format::Json => "JSON",
+            Format::Toml => "TOML",
+            Format::Scxml => "SCXML",
+            Format::PlantUml => "PlantUML",
+            Format::Kiss2 => "KISS2",
+        }
+    }
+}
+
+/// The current version of the YAML, JSON and TOML formats.
+pub const VERSION: u32 = 1;
+
+/// The type of a machine as given by the `type` field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum MachineType {
+    Moore,
+    Mealy,
+}
+
+/// The fields that decide how the rest of the file is read. All other fields are ignored.
+#[derive(Debug, Deserialize)]
+struct Header {
+    #[serde(rename = "type")]
+    machine_type: Option<MachineType>,
+    version: Option<u32>,
+    output_function: Option<IgnoredAny>,
+}
+
+/// Parses the file contents and returns a `MooreMachine` or a `MealyMachine`.
+/// The type is given by the `type` field (`moore` or `mealy`). Without it, a file with an
+/// `output_function` is a Moore machine and all others are Mealy machines.
+/// The error contains the error of the deserializer for that type.
+pub fn parse(input: &str, format: Format) -> Result<StateMachine, String> {
+    match format {
+        Format::Scxml => return scxml::parse(input),
+        Format::PlantUml => return plantuml::parse(input),
+        Format::Kiss2 => return kiss::parse(input),
+        _ => {}
+    }
+
+    let header: Header = deserialize(input, format)
+        .map_err(|e| format!("The {} file is not a state machine: {}", format.name(), e))?;
+
+    if let Some(version) = header.version {
+        if version == 0 || version > VERSION {
+            return Err(format!(
+                "The {} file has version {}, but only versions up to {} are supported.",
+                format.name(),
+                version,
+                VERSION
+            ));
+        }
+    }
+
+    let machine_type = header.machine_type.unwrap_or(match header.output_function {
+        Some(_) => MachineType::Moore,
+        None => MachineType::Mealy,
+    });
+    let hint = match header.machine_type {
+        Some(_) => "",
+        None => "\nThe type was inferred from the output_function field. Set `type: moore` or `type: mealy` to choose it.",
+    };
+
+    let result = match machine_type {
+        MachineType::Moore => deserialize(input, format).map(StateMachine::Moore),
+        MachineType::Mealy => deserialize(input, format).map(StateMachine::Mealy),
+    };
+    result.map_err(|e| {
+        format!(
+            "The {} file is not a valid {:?} machine: {}{}",
+            format.name(),
+            machine_type,
+            e.trim_end(),
+            hint
+        )
+    })
+}
+
+/// Deserializes the file contents in the given format.
+fn deserialize<T: DeserializeOwned>(input: &str, format: Format) -> Result<T, String> {
+    match format {
+        Format::Yaml => serde_yaml::from_str(input).map_err(|e| e.to_string()),
+        Format::Json => serde_json::from_str(input).map_err(|e| e.to_string()),
+        Format::Toml => toml::from_str(input).map_err(|e| e.to_string()),
+        Format::Scxml | Format::PlantUml | Format::Kiss2 => {
+            unreachable!("{} is not read with serde", format.name())
+        }
+    }
+}
+
+/// Returns the JSON Schema of the YAML, JSON and TOML formats.
+pub fn schema() -> String {
+    let mut schema = schemars::schema_for!(StateMachine);
+
+    // The fields of the header are read before the machine.
+    for (definition, machine_type) in [
+        ("Machine_MooreTransition", "moore"),
+        ("Machine_MealyTransition", "mealy"),
+    ] {
+        let properties = &mut schema.get_mut("$defs").unwrap()[definition]["properties"];
+        properties["type"] = json!({
+            "description": "The type of the machine. If not set, a machine with an output_function is a Moore machine.",
+            "const": machine_type,
+        });
+        properties["version"] = json!({
+            "description": "The version of the format.",
+            "type": "integer",
+            "minimum": 1,
+            "maximum": VERSION,
+        });
+    }
+
+    serde_json::to_string_pretty(&schema).unwrap() + "\n"
+}
+
+/// A transition read from a diagram format. Its outputs decide the kind of the machine.
+#[derive(Debug)]
+pub(crate) struct Transition {
+    pub from: String,
+    pub to: String,
+    pub guard: Vec<String>,
+    pub outputs: Vec<String>,
+}
+
+/// A state machine read from a diagram format (SCXML, PlantUML, KISS2) or built with the
+/// `MachineBuilder`, where Moore and Mealy machines are written the same way.
+#[derive(Debug, Default)]
+pub(crate) struct Diagram {
+    pub states: Vec<String>,
+    pub inputs: Vec<String>,
+    pub outputs: Vec<String>,
+    pub start_state: String,
+    pub end_states: Vec<String>,
+    pub state_outputs: Vec<(String, Vec<String>)>,
+    pub transitions: Vec<Transition>,
+    pub variables: Vec<Variable>,
+}
+
+impl Diagram {
+    /// Returns a Mealy machine if a transition has outputs, otherwise a Moore machine.
+    /// In a Mealy machine, every transition must have at most one output and the states none.
+    /// A transition without output gets an empty output symbol.
+    /// The symbols of the guards are added to the inputs, and the outputs are collected in order.
+    pub fn into_machine(self) -> Result<StateMachine, String> {
+        let Diagram {
+            states,
+            mut inputs,
+            mut outputs,
+            start_state,
+            end_states,
+            state_outputs,
+            transitions,
+            variables,
+        } = self;
+
+        for transition in &transitions {
+            for term in &transition.guard {
+                push_unique(&mut inputs, term.trim_start_matches('!').to_string());
+            }
+        }
+        for symbols in state_outputs
+            .iter()
+            .map(|(_, symbols)| symbols)
+            .chain(transitions.iter().map(|t| &t.outputs))
+        {
+            for output in symbols {
+                push_unique(&mut outputs, output.clone());
+            }
+        }
+
+        if transitions.iter().all(|t| t.outputs.is_empty()) {
+            return Ok(StateMachine::Moore(MooreMachine {
+                states,
+                input_alphabet: inputs,
+                output_alphabet: outputs,
+                transitions: transitions
+                    .into_iter()
+                    .map(|t| MooreTransition {
+                        current_state: t.from,
+                        read_symbol: t.guard,
+                        new_state: t.to,
+                    })
+                    .collect(),
+                output_function: state_outputs
+                    .into_iter()
+                    .map(|(state, symbols)| MooreOutputFunction {
+                        current_state: state,
+                        output_symbol: symbols,
+                    })
+                    .collect(),
+                start_state,
+                end_states,
+                variables,
+            }));
+        }
+
+        if let Some((state, _)) = state_outputs
+            .iter()
+            .find(|(_, symbols)| !symbols.is_empty())
+        {
+            return Err(format!(
+                "The state {} has outputs, but the transitions have outputs as well. A machine is either Moore or Mealy.",
+                state
+            ));
+        }
+        let transitions = transitions
+            .into_iter()
+            .map(|mut t| match t.outputs.len() {
+                0 | 1 => Ok(MealyTransition {
+                    current_state: t.from,
+                    read_symbol: t.guard,
+                    new_state: t.to,
+                    output_symbol: t.outputs.pop().unwrap_or_default(),
+                }),
+                _ => Err(format!(
+                    "The transition from {} to {} must have at most one output in a Mealy machine.",
+                    t.from, t.to
+                )),
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(StateMachine::Mealy(MealyMachine {
+            states,
+            input_alphabet: inputs,
+            output_alphabet: outputs,
+            transitions,
+            output_function: None,
+            start_state,
+            end_states,
+            variables,
+        }))
+    }
+}
+
+/// Appends the item to the list if it is not in it yet.
+pub(crate) fn push_unique(list: &mut Vec<String>, item: String) {
+    if !list.contains(&item) {
+        list.push(item);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(Format::from_path(Path::new("a.json")), Format::Json);
+        assert_eq!(Format::from_path(Path::new("dir/a.toml")), Format::Toml);
+        assert_eq!(Format::from_path(Path::new("a.scxml")), Format::Scxml);
+        assert_eq!(Format::from_path(Path::new("a.puml")), Format::PlantUml);
+        assert_eq!(Format::from_path(Path::new("dk14.kiss2")), Format::Kiss2);
+        assert_eq!(Format::from_path(Path::new("a.yml")), Format::Yaml);
+        assert_eq!(Format::from_path(Path::new("a")), Format::Yaml);
+    }
+
+    #[test]
+    fn test_parse_json() {
+        let json = include_str!("../resources/test_moore.json");
+
+        match parse(json, Format::Json).unwrap() {
+            StateMachine::Moore(m) => assert_eq!(m.output_function[0].output_symbol, ["o5", "o6"]),
+            _ => panic!("Wrong state machine type."),
+        }
+    }
+
+    #[test]
+    fn test_parse_toml() {
+        let toml = include_str!("../resources/test_mealy.toml");
+
+        match parse(toml, Format::Toml).unwrap() {
+            StateMachine::Mealy(m) => assert_eq!(m.transitions[0].read_symbol, ["i0", "!i1"]),
+            _ => panic!("Wrong state machine type."),
+        }
+    }
+
+    #[test]
+    fn test_parse_error_reports_serde_errors() {
+        let json = r#"{"states": ["q1"], "input_alphabet": 3}"#;
+        let error = parse(json, Format::Json).unwrap_err();
+
+        assert!(
+            error.contains("The JSON file is not a valid Mealy machine: invalid type: integer `3`")
+        );
+        assert!(error.contains("line 1 column"));
+        assert!(error.contains("inferred"));
+    }
+
+    #[test]
+    fn test_parse_type() {
+        let yaml_str = include_str!("../resources/test_moore.yaml");
+        let moore = format!("type: moore\nversion: 1\n{}", yaml_str);
+        let mealy = format!("type: mealy\n{}", yaml_str);
+
+        assert!(matches!(
+            parse(&moore, Format::Yaml),
+            Ok(StateMachine::Moore(_))
+        ));
+        // The error is the one of the given type, not of the inferred one.
+        let error = parse(&mealy, Format::Yaml).unwrap_err();
+        assert!(error.starts_with("The YAML file is not a valid Mealy machine: transitions[0]: missing field `output_symbol`"));
+        assert!(!error.contains("inferred"));
+    }
+
+    #[test]
+    fn test_parse_header_errors() {
+        let yaml_str = include_str!("../resources/test_mealy.yaml");
+        let version = format!("version: 2\n{}", yaml_str);
+        let machine_type = format!("type: moorish\n{}", yaml_str);
+
+        assert!(parse(&version, Format::Yaml)
+            .unwrap_err()
+            .contains("version 2"));
+        assert!(parse(&machine_type, Format::Yaml)
+            .unwrap_err()
+            .contains("unknown variant `moorish`, expected `moore` or `mealy`"));
+        assert!(parse("[1, 2]", Format::Json)
+            .unwrap_err()
+            .contains("not a state machine"));
+    }
+
+    #[test]
+    fn test_schema_is_up_to_date() {
+        // Regenerate with: UPDATE_SCHEMA=1 cargo test test_schema_is_up_to_date
+        let path =
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("resources/state_machine.schema.json");
+        if std::env::var_os("UPDATE_SCHEMA").is_some() {
+            std::fs::write(&path, schema()).unwrap();
+        }
+
+        assert_eq!(
+            std::fs::read_to_string(path).unwrap(),
+            schema(),
+            "The JSON Schema is outdated. Run: UPDATE_SCHEMA=1 cargo test test_schema_is_up_to_date"
+        );
+    }
+}
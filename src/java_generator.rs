@@ -0,0 +1,268 @@
+/*!
+Java and Kotlin code generator
+
+This module generates a Java class with an enum-based state machine from a state
+machine. Every enum constant overrides `next(Input)` with the transitions of its state.
+For Kotlin, the states are objects of a sealed class instead.
+
+The generated code is written to a file or directory.
+*/
+
+use heck::ToUpperCamelCase;
+use minijinja::{context, Value};
+
+use crate::files::Files;
+use crate::model::Model;
+use crate::state_machines::StateMachine;
+use crate::templates::Templates;
+
+/// The language of the Java templates in `resources/templates`.
+pub const LANGUAGE: &str = "java";
+
+/// The language of the Kotlin templates in `resources/templates`.
+pub const KOTLIN_LANGUAGE: &str = "kotlin";
+
+/// Generates the Java class for the state machine. The code is written to a file or directory.
+/// The name in PascalCase is used for the file and class name.
+/// If a package is given, the class is put into it.
+pub fn generate(
+    name: &str,
+    state_machine: &StateMachine,
+    templates: &Templates,
+    package: Option<&str>,
+    files: Files,
+) -> Result<Files, String> {
+    generate_file(name, state_machine, templates, package, "java", files)
+}
+
+/// Generates the Kotlin class for the state machine. The code is written to a file or directory.
+/// The name in PascalCase is used for the file and class name.
+/// If a package is given, the class is put into it.
+pub fn generate_kotlin(
+    name: &str,
+    state_machine: &StateMachine,
+    templates: &Templates,
+    package: Option<&str>,
+    files: Files,
+) -> Result<Files, String> {
+    generate_file(name, state_machine, templates, package, "kt", files)
+}
+
+/// Renders `Machine.<extension>` into `<ClassName>.<extension>`.
+fn generate_file(
+    name: &str,
+    state_machine: &StateMachine,
+    templates: &Templates,
+    package: Option<&str>,
+    extension: &str,
+    mut files: Files,
+) -> Result<Files, String> {
+    let model = Model::new(name, state_machine);
+    let context = context! { package, ..Value::from_serialize(&model) };
+
+    files.add_file(
+        format!("{}.{}", name.to_upper_camel_case(), extension),
+        templates.render(&format!("Machine.{}", extension), &context)?,
+    );
+
+    Ok(files)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_support::{assert_golden, parse, run_tool, temp_dir};
+    use std::path::Path;
+    use std::process::Command;
+
+    fn render(yaml_str: &str, language: &str, template: &str) -> String {
+        let model = Model::new("motor_control", &parse(yaml_str));
+        let context = context! { package => "com.example", ..Value::from_serialize(&model) };
+        let templates = Templates::new(language, None).unwrap();
+        templates.render(template, &context).unwrap()
+    }
+
+    /// Generates the Java class, compiles it with a main class, and runs it.
+    fn assert_runs(name: &str, yaml_str: &str, main: &str) {
+        let dir = temp_dir(&format!("java_{}", name));
+        let templates = Templates::new(LANGUAGE, None).unwrap();
+        let files = generate(
+            name,
+            &parse(yaml_str),
+            &templates,
+            None,
+            Files::new(dir.clone()),
+        );
+        files.unwrap().write().unwrap();
+        std::fs::write(dir.join("Main.java"), main).unwrap();
+
+        let sources = [
+            dir.join(format!("{}.java", name.to_upper_camel_case())),
+            dir.join("Main.java"),
+        ];
+        let mut args = vec!["-Werror".into(), "-d".into(), dir.clone()];
+        args.extend(sources);
+        run_tool("JAVAC", "javac", &args);
+
+        let status = Command::new("java")
+            .args([Path::new("-cp"), &dir, Path::new("Main")])
+            .status()
+            .unwrap();
+        assert_eq!(status.code(), Some(0));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_java_moore() {
+        assert_runs(
+            "motor",
+            include_str!("../resources/test_moore.yaml"),
+            r#"public class Main {
+    public static void main(String[] args) {
+        Motor motor = new Motor();
+        Motor.Input input = new Motor.Input();
+        input.i0 = true;
+        input.i1 = true;
+
+        if (!motor.step(input).o5 || motor.state() != Motor.State.q2) System.exit(1);
+        if (motor.previousState() != Motor.State.q1) System.exit(2);
+        if (motor.step(input).o5 || !motor.isEndState()) System.exit(3);
+
+        motor.reset();
+        System.exit(motor.state() == Motor.State.q1 ? 0 : 4);
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_java_mealy() {
+        assert_runs(
+            "pairing",
+            include_str!("../resources/test_mealy.yaml"),
+            r#"public class Main {
+    public static void main(String[] args) {
+        Pairing pairing = new Pairing();
+        Pairing.Input input = new Pairing.Input();
+        input.i0 = true;
+
+        if (!pairing.step(input).o0 || pairing.state() != Pairing.State.q2) System.exit(1);
+        if (pairing.step(input).o0 || pairing.state() != Pairing.State.q2) System.exit(2);
+        input.i0 = false;
+        if (!pairing.step(input).o0 || pairing.state() != Pairing.State.q1) System.exit(3);
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_java_variables_and_no_guards() {
+        let yaml_str = "
+states: [idle, running]
+input_alphabet: []
+transitions:
+  - current_state: idle
+    read_symbol: []
+    new_state: running
+output_function: []
+start_state: idle
+end_states: []
+output_alphabet: []
+variables:
+  - name: counter
+    type: uint
+  - name: gain
+    type: float
+    initial: 2
+";
+        assert_runs(
+            "minimal",
+            yaml_str,
+            r#"public class Main {
+    public static void main(String[] args) {
+        Minimal minimal = new Minimal();
+        minimal.step(new Minimal.Input());
+        boolean ok = minimal.state() == Minimal.State.running && minimal.gain == 2.0;
+        System.exit(ok && !minimal.isEndState() ? 0 : 1);
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_java_package() {
+        let code = render(
+            include_str!("../resources/test_moore.yaml"),
+            LANGUAGE,
+            "Machine.java",
+        );
+
+        assert!(code.contains("\npackage com.example;\n"));
+        assert!(code.contains("public final class MotorControl {"));
+    }
+
+    /// Pins the generated classes, so that changes show up without a Kotlin compiler.
+    /// The Mealy machine is put into a package.
+    #[test]
+    fn test_golden() {
+        for (language, extension) in [(LANGUAGE, "java"), (KOTLIN_LANGUAGE, "kt")] {
+            let templates = Templates::new(language, None).unwrap();
+            for (name, yaml_str, package) in [
+                ("moore", include_str!("../resources/test_moore.yaml"), None),
+                (
+                    "mealy",
+                    include_str!("../resources/test_mealy.yaml"),
+                    Some("com.example"),
+                ),
+            ] {
+                let files = generate_file(
+                    name,
+                    &parse(yaml_str),
+                    &templates,
+                    package,
+                    extension,
+                    Files::new("".into()),
+                );
+                assert_golden(language, &files.unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_kotlin_moore() {
+        let code = render(
+            include_str!("../resources/test_moore.yaml"),
+            KOTLIN_LANGUAGE,
+            "Machine.kt",
+        );
+
+        assert!(code.contains("\npackage com.example\n"));
+        assert!(code.contains("class MotorControl {"));
+        assert!(code.contains("    class Input(\n        var i0: Boolean = false,\n        var i1: Boolean = false,\n    )"));
+        assert!(code.contains("    sealed class State {"));
+        assert!(code.contains(
+            "        object q1 : State() {\n            override fun next(input: Input): State {\n                if (input.i0 && input.i1) {\n                    return q2\n                }\n                return this\n            }\n"
+        ));
+        assert!(code.contains(
+            "            override fun output(input: Input, output: Output) {\n                output.o5 = true\n                output.o6 = true\n            }\n"
+        ));
+        assert!(code.contains("        get() = state == State.q3\n\n"));
+    }
+
+    #[test]
+    fn test_kotlin_mealy() {
+        let code = render(
+            include_str!("../resources/test_mealy.yaml"),
+            KOTLIN_LANGUAGE,
+            "Machine.kt",
+        );
+
+        assert!(code.contains(
+            "                if (input.i1) {\n                    output.o1 = true\n                }\n                else if (!input.i0) {\n                    output.o0 = true\n                }\n"
+        ));
+    }
+}
@@ -0,0 +1,544 @@
+/*!
+KISS2 reader and writer, BLIF writer
+
+This module converts between state machines and the KISS2 format of logic synthesis
+tools and benchmarks (e.g. the MCNC suite), and writes the encoded machine as BLIF.
+
+A KISS2 file is a state table. Every row has an input cube (`0`, `1` or `-` per input),
+the present state, the next state and the output bits:
+
+```text
+.i 2
+.o 2
+.s 3
+.r q1
+11 q1 q2 11
+```
+
+The rows of a state are disjoint, while the transitions of a state machine are checked
+in order. When writing, every guard is reduced by the guards before it, and the inputs
+for which no transition fires get a row that stays in the state. When reading, these rows
+are dropped again. KISS2 has no names for the inputs and outputs, so they are read as
+`i0`, `i1`, ... and `o0`, `o1`, ... KISS2 has no end states either: they are not
+written, and a machine read from KISS2 has none.
+
+The BLIF file is the netlist of the state table with the states encoded like the
+SystemVerilog state register: a latch per state bit, and the next state and output logic.
+*/
+
+use std::collections::HashMap;
+
+use minijinja::{context, Value};
+use serde::Serialize;
+
+use crate::files::Files;
+use crate::input::{push_unique, Diagram, Transition};
+use crate::model::{Kind, Model};
+use crate::state_machines::StateMachine;
+use crate::templates::Templates;
+use crate::verilog_generator::Encoding;
+
+/// The language of the KISS2 templates in `resources/templates`.
+pub const LANGUAGE: &str = "kiss2";
+
+/// The language of the BLIF templates in `resources/templates`.
+pub const BLIF_LANGUAGE: &str = "blif";
+
+/// A row of the state table.
+#[derive(Debug, Serialize)]
+struct Row {
+    inputs: String,
+    from: String,
+    to: String,
+    outputs: String,
+}
+
+/// Generates the KISS2 state table for the state machine. The table is written to a file or directory.
+/// The name is used for the file name. The end states are not written.
+pub fn generate(
+    name: &str,
+    state_machine: &StateMachine,
+    templates: &Templates,
+    mut files: Files,
+) -> Result<Files, String> {
+    let model = Model::new(name, state_machine);
+
+    files.add_file(
+        format!("{}.kiss2", name),
+        templates.render("machine.kiss2", &create_context(&model)?)?,
+    );
+
+    Ok(files)
+}
+
+/// Generates the BLIF netlist for the state machine with the states encoded by `encoding`.
+/// The netlist is written to a file or directory. The name is used for the file and model name.
+pub fn generate_blif(
+    name: &str,
+    state_machine: &StateMachine,
+    templates: &Templates,
+    encoding: Encoding,
+    mut files: Files,
+) -> Result<Files, String> {
+    let model = Model::new(name, state_machine);
+
+    files.add_file(
+        format!("{}.blif", name),
+        templates.render("machine.blif", &create_blif_context(&model, encoding)?)?,
+    );
+
+    Ok(files)
+}
+
+/// Creates the template context: the model with the rows of the state table.
+fn create_context(model: &Model) -> Result<Value, String> {
+    Ok(context! {
+        rows => table(model)?,
+        ..Value::from_serialize(model)
+    })
+}
+
+/// Creates the BLIF template context: the state table with the encoding, the names of the
+/// state bits, and the code of every state.
+fn create_blif_context(model: &Model, encoding: Encoding) -> Result<Value, String> {
+    let width = encoding.width(model.states.len());
+    let codes = model
+        .states
+        .iter()
+        .map(|s| (s.name.clone(), encoding.bits(s.index, width)))
+        .collect::<HashMap<String, String>>();
+
+    Ok(context! {
+        encoding => encoding.name(),
+        state_width => width,
+        state_bits => (0..width).map(|bit| format!("state_{}", bit)).collect::<Vec<_>>(),
+        state_codes => codes,
+        ..create_context(model)?
+    })
+}
+
+/// Returns the state table of the model with disjoint input cubes per state.
+fn table(model: &Model) -> Result<Vec<Row>, String> {
+    let mut rows = Vec::new();
+
+    for state in &model.states {
+        let mut previous: Vec<Vec<u8>> = Vec::new();
+
+        for transition in &state.transitions {
+            let Some(guard) = cube(model, transition)? else {
+                continue; // The guard contains an input and its negation.
+            };
+            let outputs = match model.kind {
+                Kind::Moore => &state.outputs,
+                Kind::Mealy => &transition.outputs,
+            };
+            for inputs in subtract(vec![guard.clone()], &previous) {
+                rows.push(Row {
+                    inputs: String::from_utf8(inputs).unwrap(),
+                    from: state.name.clone(),
+                    to: transition.to.clone(),
+                    outputs: output_bits(model, outputs),
+                });
+            }
+            previous.push(guard);
+        }
+
+        // No transition fires: the state stays, and a Mealy machine has no outputs.
+        let outputs = match model.kind {
+            Kind::Moore => output_bits(model, &state.outputs),
+            Kind::Mealy => output_bits(model, &[]),
+        };
+        for inputs in subtract(vec![vec![b'-'; model.inputs.len()]], &previous) {
+            rows.push(Row {
+                inputs: String::from_utf8(inputs).unwrap(),
+                from: state.name.clone(),
+                to: state.name.clone(),
+                outputs: outputs.clone(),
+            });
+        }
+    }
+
+    Ok(rows)
+}
+
+/// Returns the input cube of the guard, or `None` if the guard can never hold.
+/// Fails if the guard reads a symbol that is not an input.
+fn cube(model: &Model, transition: &crate::model::Transition) -> Result<Option<Vec<u8>>, String> {
+    let mut cube = vec![b'-'; model.inputs.len()];
+    for term in &transition.guard {
+        let index = model
+            .inputs
+            .iter()
+            .position(|i| *i == term.symbol)
+            .ok_or_else(|| {
+                format!(
+                    "The transition from {} to {} reads {}, which is not an input.",
+                    transition.from, transition.to, term.symbol
+                )
+            })?;
+        let bit = if term.negated { b'0' } else { b'1' };
+        if cube[index] != b'-' && cube[index] != bit {
+            return Ok(None);
+        }
+        cube[index] = bit;
+    }
+    Ok(Some(cube))
+}
+
+/// Removes the cubes in `others` from the cubes. The resulting cubes are disjoint.
+fn subtract(mut cubes: Vec<Vec<u8>>, others: &[Vec<u8>]) -> Vec<Vec<u8>> {
+    for other in others {
+        cubes = cubes.iter().flat_map(|c| sharp(c, other)).collect();
+    }
+    cubes
+}
+
+/// Returns disjoint cubes that cover `a` without `b`.
+fn sharp(a: &[u8], b: &[u8]) -> Vec<Vec<u8>> {
+    let disjoint = a
+        .iter()
+        .zip(b)
+        .any(|(x, y)| *x != b'-' && *y != b'-' && x != y);
+    if disjoint {
+        return vec![a.to_vec()];
+    }
+
+    let mut result = Vec::new();
+    let mut rest = a.to_vec();
+    for (index, bit) in b.iter().enumerate() {
+        if *bit != b'-' && rest[index] == b'-' {
+            let mut cube = rest.clone();
+            cube[index] = if *bit == b'1' { b'0' } else { b'1' };
+            result.push(cube);
+            rest[index] = *bit;
+        }
+    }
+    result
+}
+
+/// Returns the output bits, one per output of the model.
+fn output_bits(model: &Model, outputs: &[String]) -> String {
+    model
+        .outputs
+        .iter()
+        .map(|o| if outputs.contains(o) { '1' } else { '0' })
+        .collect()
+}
+
+/// Parses a KISS2 state table and returns a `MooreMachine` or a `MealyMachine`.
+/// If the outputs of every state do not depend on the inputs, the machine is a Moore machine.
+/// Otherwise, it is a Mealy machine, where an output vector with more than one set bit
+/// becomes a single output named after the bits (e.g. `o_101`).
+pub fn parse(text: &str) -> Result<StateMachine, String> {
+    let mut input_count = None;
+    let mut output_count = None;
+    let mut start_state = None;
+    let mut rows = Vec::new();
+
+    for (index, line) in text.lines().enumerate() {
+        let line = line.split('#').next().unwrap().trim();
+        let error = |message: String| format!("Line {}: {}", index + 1, message);
+        let mut fields = line.split_whitespace();
+
+        match fields.next() {
+            None => continue,
+            Some(".i") => input_count = Some(parse_count(fields.next()).map_err(error)?),
+            Some(".o") => output_count = Some(parse_count(fields.next()).map_err(error)?),
+            Some(".p" | ".s") => continue,
+            Some(".r") => {
+                let state = fields
+                    .next()
+                    .ok_or_else(|| error("Expected a state.".into()))?;
+                start_state = Some(state.to_string());
+            }
+            Some(".e" | ".end") => break,
+            Some(directive) if directive.starts_with('.') => {
+                return Err(error(format!("Unknown directive {}.", directive)))
+            }
+            Some(first) => {
+                let (Some(inputs), Some(outputs)) = (input_count, output_count) else {
+                    return Err(error("The rows must follow .i and .o.".into()));
+                };
+                let mut fields = std::iter::once(first).chain(fields);
+                let mut field = |count: usize| match count {
+                    0 => Some(""),
+                    _ => fields.next(),
+                };
+                let (Some(cube), Some(from), Some(to), Some(bits)) =
+                    (field(inputs), field(1), field(1), field(outputs))
+                else {
+                    return Err(error(
+                        "Expected <inputs> <state> <next state> <outputs>.".into(),
+                    ));
+                };
+                if fields.next().is_some() {
+                    return Err(error("Too many fields.".into()));
+                }
+                if cube.len() != inputs || !cube.bytes().all(|b| b"01-".contains(&b)) {
+                    return Err(error(format!("Invalid input cube {}.", cube)));
+                }
+                if bits.len() != outputs || !bits.bytes().all(|b| b"01-".contains(&b)) {
+                    return Err(error(format!("Invalid outputs {}.", bits)));
+                }
+                if from == "*" || to == "*" {
+                    return Err(error("Unspecified states (*) are not supported.".into()));
+                }
+                rows.push(Row {
+                    inputs: cube.to_string(),
+                    from: from.to_string(),
+                    to: to.to_string(),
+                    // Don't care outputs are not set.
+                    outputs: bits.replace('-', "0"),
+                });
+            }
+        }
+    }
+
+    let mut states = Vec::new();
+    for row in &rows {
+        push_unique(&mut states, row.from.clone());
+        push_unique(&mut states, row.to.clone());
+    }
+    let start_state = start_state
+        .or_else(|| states.first().cloned())
+        .ok_or("The KISS2 file does not contain any states.")?;
+    push_unique(&mut states, start_state.clone());
+
+    let inputs = (0..input_count.unwrap_or(0))
+        .map(|i| format!("i{}", i))
+        .collect::<Vec<_>>();
+    let outputs = |bits: &str| {
+        bits.bytes()
+            .enumerate()
+            .filter(|(_, bit)| *bit == b'1')
+            .map(|(index, _)| format!("o{}", index))
+            .collect::<Vec<_>>()
+    };
+
+    // Moore: all rows of a state have the same outputs.
+    let state_outputs = states
+        .iter()
+        .map(|state| {
+            let mut bits = rows.iter().filter(|r| r.from == *state).map(|r| &r.outputs);
+            let first = bits.next();
+            bits.all(|b| Some(b) == first)
+                .then(|| (state.clone(), first.map(|b| outputs(b)).unwrap_or_default()))
+        })
+        .collect::<Option<Vec<_>>>();
+
+    let mut transitions = Vec::new();
+    for row in &rows {
+        let stays_without_outputs =
+            row.from == row.to && (state_outputs.is_some() || !row.outputs.contains('1'));
+        if stays_without_outputs {
+            continue; // The same as no transition.
+        }
+        let guard = row
+            .inputs
+            .bytes()
+            .zip(&inputs)
+            .filter_map(|(bit, input)| match bit {
+                b'1' => Some(input.clone()),
+                b'0' => Some(format!("!{}", input)),
+                _ => None,
+            })
+            .collect();
+        let outputs = match (&state_outputs, outputs(&row.outputs)) {
+            (Some(_), _) => Vec::new(),
+            (None, set) if set.len() <= 1 => set,
+            (None, _) => vec![format!("o_{}", row.outputs)],
+        };
+        transitions.push(Transition {
+            from: row.from.clone(),
+            to: row.to.clone(),
+            guard,
+            outputs,
+        });
+    }
+
+    Diagram {
+        states,
+        inputs,
+        start_state,
+        end_states: Vec::new(),
+        state_outputs: state_outputs.unwrap_or_default(),
+        transitions,
+        ..Default::default()
+    }
+    .into_machine()
+}
+
+fn parse_count(field: Option<&str>) -> Result<usize, String> {
+    field
+        .and_then(|f| f.parse().ok())
+        .ok_or_else(|| "Expected a number.".to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_support::parse as parse_yaml;
+
+    fn export(yaml_str: &str) -> String {
+        let model = Model::new("test", &parse_yaml(yaml_str));
+        let templates = Templates::new(LANGUAGE, None).unwrap();
+        templates
+            .render("machine.kiss2", &create_context(&model).unwrap())
+            .unwrap()
+    }
+
+    /// Checks that the table has exactly one row for every state and input, and that
+    /// it is the transition that fires in the model.
+    fn assert_table_matches_model(model: &Model) {
+        let rows = table(model).unwrap();
+        for state in &model.states {
+            for bits in 0..(1usize << model.inputs.len()) {
+                let value = |index: usize| bits & (1 << index) != 0;
+                let matching = rows
+                    .iter()
+                    .filter(|r| r.from == state.name)
+                    .filter(|r| {
+                        r.inputs.bytes().enumerate().all(|(i, b)| match b {
+                            b'1' => value(i),
+                            b'0' => !value(i),
+                            _ => true,
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                assert_eq!(matching.len(), 1, "{} with input {:b}", state.name, bits);
+
+                let input =
+                    |symbol: &str| value(model.inputs.iter().position(|i| i == symbol).unwrap());
+                let expected = state.next(input).map_or(&state.name, |t| &t.to);
+                assert_eq!(
+                    &matching[0].to, expected,
+                    "{} with input {:b}",
+                    state.name, bits
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_export_moore() {
+        let kiss = export(include_str!("../resources/test_moore.yaml"));
+
+        assert!(kiss
+            .starts_with(".i 2\n.o 2\n.p 6\n.s 3\n.r q1\n11 q1 q2 11\n0- q1 q1 11\n10 q1 q1 11\n"));
+        assert!(kiss.ends_with("-- q3 q3 00\n.e\n"));
+    }
+
+    #[test]
+    fn test_table_is_disjoint() {
+        // The second transition overlaps the first one and is reduced by it.
+        let yaml_str = include_str!("../resources/test_mealy.yaml")
+            .replace("read_symbol: [\"!i0\"]", "read_symbol: [\"!i1\"]");
+
+        assert_table_matches_model(&Model::new("test", &parse_yaml(&yaml_str)));
+        assert_table_matches_model(&Model::new(
+            "test",
+            &parse_yaml(include_str!("../resources/test_moore.yaml")),
+        ));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for yaml_str in [
+            include_str!("../resources/test_moore.yaml"),
+            include_str!("../resources/test_mealy.yaml"),
+        ] {
+            let original = Model::new("test", &parse_yaml(yaml_str));
+            let model = Model::new("test", &parse(&export(yaml_str)).unwrap());
+
+            assert_eq!(model.kind, original.kind);
+            assert_eq!(model.inputs, original.inputs);
+            assert_eq!(model.start_state, original.start_state);
+            // The guards are reduced to disjoint cubes, but the same transitions fire.
+            for (state, original_state) in model.states.iter().zip(&original.states) {
+                assert_eq!(state.name, original_state.name);
+                for bits in 0..4 {
+                    let input = |symbol: &str| bits & (1 << (symbol == "i1") as usize) != 0;
+                    let to = |t: Option<&crate::model::Transition>| t.map(|t| t.to.clone());
+                    assert_eq!(to(state.next(input)), to(original_state.next(input)));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_mealy_output_vectors() {
+        let kiss = "# comment
+.i 1
+.o 2
+.r b
+1 a b 10
+0 a a 11
+- b a 0-
+.e";
+        let machine = match parse(kiss).unwrap() {
+            StateMachine::Mealy(m) => m,
+            _ => panic!("Wrong state machine type."),
+        };
+
+        assert_eq!(machine.start_state, "b");
+        // A row without outputs is a transition without output.
+        assert_eq!(machine.output_alphabet, ["o0", "o_11"]);
+        assert_eq!(machine.transitions[1].output_symbol, "o_11");
+        assert_eq!(machine.transitions[2].output_symbol, "");
+
+        // It is written back as a row without outputs.
+        let rows = table(&Model::new("test", &parse(kiss).unwrap())).unwrap();
+        assert!(rows
+            .iter()
+            .any(|r| r.from == "b" && r.to == "a" && r.outputs == "00"));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse(".i 1\n.o 1\n2 a b 1")
+            .unwrap_err()
+            .contains("Line 3: Invalid input cube"));
+        assert!(parse(".i 1\n.o 1\n1 a b").unwrap_err().contains("Expected"));
+        assert!(parse(".i 1\n.o 1\n1 * b 1").unwrap_err().contains("(*)"));
+        assert!(parse("1 a b 1").unwrap_err().contains(".i and .o"));
+        assert_eq!(
+            parse(".i 1\n.o 1\n.r\n1 a b 1").unwrap_err(),
+            "Line 3: Expected a state."
+        );
+    }
+
+    #[test]
+    fn test_export_unknown_symbol() {
+        let yaml_str = include_str!("../resources/test_moore.yaml")
+            .replace("read_symbol: [\"i1\"]", "read_symbol: [\"i7\"]");
+        let model = Model::new("test", &parse_yaml(&yaml_str));
+
+        assert_eq!(
+            table(&model).unwrap_err(),
+            "The transition from q2 to q3 reads i7, which is not an input."
+        );
+    }
+
+    #[test]
+    fn test_blif() {
+        let model = Model::new(
+            "test",
+            &parse_yaml(include_str!("../resources/test_moore.yaml")),
+        );
+        let templates = Templates::new(BLIF_LANGUAGE, None).unwrap();
+        let blif = templates
+            .render(
+                "machine.blif",
+                &create_blif_context(&model, Encoding::Binary).unwrap(),
+            )
+            .unwrap();
+
+        assert!(blif.contains(".model test\n.inputs i0 i1\n.outputs o5 o6\n"));
+        assert!(blif.contains(".latch next_state_0 state_0 0\n.latch next_state_1 state_1 0\n"));
+        // q1 (00) goes to q2 (01) with i0 and i1, q2 goes to q3 (10) with i1, q3 stays.
+        assert!(blif.contains(".names i0 i1 state_0 state_1 next_state_0\n-101 1\n--10 1\n"));
+        assert!(blif.contains(".names i0 i1 state_0 state_1 next_state_1\n1100 1\n-001 1\n"));
+        assert!(blif.contains(".names i0 i1 state_0 state_1 o5\n1100 1\n0-00 1\n1000 1\n"));
+        assert!(blif.ends_with(".end\n"));
+    }
+}
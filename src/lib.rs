@@ -0,0 +1,67 @@
+/*!
+State Machine Builder
+
+Generates code for Moore and Mealy machines. Besides the command-line tool, the
+library can be used from build scripts (`build.rs`) and other tools:
+
+- [`input::parse`] reads a machine from YAML, JSON, TOML, SCXML, PlantUML or KISS2.
+- [`MachineBuilder`] builds a machine in code.
+- [`writer::to_yaml`] writes a machine as YAML in a canonical layout.
+- [`checks::validate_state_machine`] runs all checks on a machine, and [`checks::find_all`]
+  collects all findings for [`report`].
+- The generators, e.g. [`c_generator::generate`], render the code with [`templates::Templates`]
+  into [`files::Files`].
+- [`executor::Executor`] runs a machine loaded at runtime without generating code.
+- [`build_script::generate`] checks a machine file and writes Rust or C code into `OUT_DIR`.
+
+```
+use state_machine_builder::files::Files;
+use state_machine_builder::templates::Templates;
+use state_machine_builder::{c_generator, MachineBuilder};
+
+let machine = MachineBuilder::new()
+    .state("idle")
+    .state("running")
+    .transition("idle", ["start"], "running")
+    .transition("running", ["!start"], "idle")
+    .state_outputs("running", ["motor_on"])
+    .build()
+    .unwrap();
+
+let templates = Templates::new(c_generator::LANGUAGE, None).unwrap();
+let dir = std::env::temp_dir().join(format!("state_machine_builder_doc_{}", std::process::id()));
+let files = c_generator::generate("motor", &machine, &templates, Files::new(dir.clone())).unwrap();
+files.write().unwrap();
+assert!(dir.join("motor.h").is_file());
+# std::fs::remove_dir_all(dir).unwrap();
+```
+*/
+
+pub mod build_script;
+pub mod builder;
+pub mod c_generator;
+pub mod checks;
+pub mod cpp_generator;
+pub mod diff;
+pub mod executor;
+pub mod files;
+pub mod go_generator;
+pub mod input;
+pub mod java_generator;
+pub mod kiss;
+pub mod model;
+pub mod plantuml;
+pub mod report;
+pub mod rust_generator;
+pub mod scxml;
+pub mod state_machines;
+pub mod templates;
+#[cfg(test)]
+mod test_support;
+pub mod typescript_generator;
+pub mod verilog_generator;
+pub mod vhdl_generator;
+pub mod writer;
+
+pub use builder::MachineBuilder;
+pub use state_machines::StateMachine;
@@ -1,71 +1,627 @@
 mod argument_parser;
-mod c_generator;
-mod checks;
-mod files;
-mod state_machines;
+mod manifest;
 
-use argument_parser::Cli;
+use argument_parser::{CheckFormat, Cli, Command, ExportFormat, GenerateArgs, Language};
 use clap::Parser;
-use state_machines::{MealyMachine, MooreMachine, StateMachine};
+use state_machine_builder::executor::Executor;
+use state_machine_builder::report::{self, Diagnostic};
+use state_machine_builder::{
+    c_generator, checks, cpp_generator, diff, files, go_generator, input, java_generator, kiss,
+    rust_generator, scxml, templates, typescript_generator, verilog_generator, vhdl_generator,
+    writer, StateMachine,
+};
+use std::collections::HashMap;
+use std::fmt::Display;
 use std::fs;
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, SystemTime};
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// The categories of failures. The exit code tells scripts what went wrong.
+/// Invalid arguments exit with 2, like all clap errors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Failure {
+    /// The machines differ, or a file is not formatted.
+    Differs = 1,
+    /// A file could not be read, or it is not a state machine.
+    Input = 3,
+    /// A machine failed a check.
+    Invalid = 4,
+    /// The code could not be generated, e.g. because of an error in a template.
+    Generate = 5,
+    /// The files could not be written.
+    Output = 6,
+}
+
+/// An error of a subcommand with its category.
+#[derive(Debug)]
+struct Error {
+    failure: Failure,
+    message: String,
+}
+
+impl Failure {
+    /// Returns an error of this category with the message.
+    fn error(self, message: impl Display) -> Error {
+        Error {
+            failure: self,
+            message: message.to_string(),
+        }
+    }
+}
+
+fn main() -> ExitCode {
     let cli = Cli::parse();
-    let yaml_str = fs::read_to_string(cli.yaml_file)?;
-    let name = cli.name;
+    let input_format = cli.input_format.map(input::Format::from);
+
+    let result = match cli.command {
+        Command::Generate(args) if args.watch => watch(&args, input_format, cli.quiet),
+        Command::Generate(args) => generate(&args, input_format, cli.quiet),
+        Command::Build {
+            manifest,
+            jobs,
+            check,
+        } => build(&manifest, jobs, check, input_format, cli.quiet),
+        Command::Check { files, format } => check(&files, format, input_format, cli.quiet),
+        Command::Simulate { file, steps } => simulate(&file, &steps, input_format),
+        Command::Export {
+            file,
+            format,
+            name,
+            encoding,
+        } => export(&file, format, name, encoding.into(), input_format),
+        Command::Fmt {
+            files,
+            check,
+            stdout,
+        } => format_files(&files, check, stdout, input_format, cli.quiet),
+        Command::Diff { old, new } => compare(&old, &new, input_format),
+        Command::Schema => {
+            print!("{}", input::schema());
+            Ok(())
+        }
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {}", error.message);
+            ExitCode::from(error.failure as u8)
+        }
+    }
+}
+
+/// Reads a machine file. The format is taken from the extension unless it is given.
+fn read_machine(path: &Path, format: Option<input::Format>) -> Result<StateMachine, Error> {
+    let contents = fs::read_to_string(path)
+        .map_err(|e| Failure::Input.error(format!("Cannot read {}: {}", path.display(), e)))?;
+    let format = format.unwrap_or_else(|| input::Format::from_path(path));
+    input::parse(&contents, format)
+        .map_err(|e| Failure::Input.error(format!("{}: {}", path.display(), e)))
+}
+
+/// Reads a machine file and runs all checks.
+fn read_valid_machine(path: &Path, format: Option<input::Format>) -> Result<StateMachine, Error> {
+    let state_machine = read_machine(path, format)?;
+    checks::validate_state_machine(&state_machine)
+        .map_err(|e| Failure::Invalid.error(format!("{}: {}", path.display(), e)))?;
+    Ok(state_machine)
+}
+
+/// Returns the name, or the name of the file without its extension. Fails if it is not
+/// an identifier (see `checks::validate_machine_name`).
+fn name_or_file_stem(name: Option<String>, path: &Path) -> Result<String, Error> {
+    let name = name.unwrap_or_else(|| {
+        path.file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    });
+
+    checks::validate_machine_name(&name).map_err(|e| {
+        Failure::Invalid.error(format!(
+            "{}: {} Set another name with --name.",
+            path.display(),
+            e
+        ))
+    })?;
+    Ok(name)
+}
+
+/// Generates the code for a machine and writes the files.
+fn generate(args: &GenerateArgs, format: Option<input::Format>, quiet: bool) -> Result<(), Error> {
+    let state_machine = read_valid_machine(&args.file, format)?;
+    let name = name_or_file_stem(args.name.clone(), &args.file)?;
 
-    let state_machine = parse_yaml(&yaml_str)?;
+    let language = match args.language {
+        Language::C => c_generator::LANGUAGE,
+        Language::Cpp => cpp_generator::LANGUAGE,
+        Language::Go => go_generator::LANGUAGE,
+        Language::Typescript => typescript_generator::LANGUAGE,
+        Language::Systemverilog => verilog_generator::LANGUAGE,
+        Language::Vhdl => vhdl_generator::LANGUAGE,
+        Language::Java => java_generator::LANGUAGE,
+        Language::Kotlin => java_generator::KOTLIN_LANGUAGE,
+        Language::Rust => rust_generator::LANGUAGE,
+        Language::Scxml => scxml::LANGUAGE,
+        Language::Kiss2 => kiss::LANGUAGE,
+        Language::Blif => kiss::BLIF_LANGUAGE,
+    };
+    let templates = templates::Templates::new(language, args.template_dir.as_deref())
+        .map_err(|e| Failure::Generate.error(e))?;
 
-    match &state_machine {
-        StateMachine::Moore(machine) => {
-            checks::validate_legal_variable_name(machine)?;
-            checks::validate_unique_elements(machine)?;
-            checks::validate_end_states(machine)?;
+    let files = files::Files::new(args.output.clone());
+    let files = match args.language {
+        Language::C => c_generator::generate(&name, &state_machine, &templates, files),
+        Language::Cpp => {
+            cpp_generator::generate(&name, &state_machine, &templates, args.hooks, files)
         }
-        StateMachine::Mealy(machine) => {
-            checks::validate_legal_variable_name(machine)?;
-            checks::validate_unique_elements(machine)?;
-            checks::validate_end_states(machine)?;
+        Language::Go => go_generator::generate(&name, &state_machine, &templates, files),
+        Language::Typescript => {
+            typescript_generator::generate(&name, &state_machine, &templates, files)
         }
+        Language::Systemverilog => verilog_generator::generate(
+            &name,
+            &state_machine,
+            &templates,
+            args.encoding.into(),
+            files,
+        ),
+        Language::Vhdl => vhdl_generator::generate(&name, &state_machine, &templates, files),
+        Language::Java => java_generator::generate(
+            &name,
+            &state_machine,
+            &templates,
+            args.package.as_deref(),
+            files,
+        ),
+        Language::Kotlin => java_generator::generate_kotlin(
+            &name,
+            &state_machine,
+            &templates,
+            args.package.as_deref(),
+            files,
+        ),
+        Language::Rust => rust_generator::generate(&name, &state_machine, &templates, files),
+        Language::Scxml => scxml::generate(&name, &state_machine, &templates, files),
+        Language::Kiss2 => kiss::generate(&name, &state_machine, &templates, files),
+        Language::Blif => kiss::generate_blif(
+            &name,
+            &state_machine,
+            &templates,
+            args.encoding.into(),
+            files,
+        ),
+    }
+    .map_err(|e| Failure::Generate.error(e))?;
+
+    if args.dry_run {
+        match files.stale()[..] {
+            [] => println!("The files in {} are up to date.", args.output.display()),
+            ref stale => print!("Would write {}:\n{}", stale.join(", "), files.diff()),
+        }
+        return Ok(());
+    }
+    if args.check {
+        return match files.stale()[..] {
+            [] if !quiet => {
+                println!("The files in {} are up to date.", args.output.display());
+                Ok(())
+            }
+            [] => Ok(()),
+            ref stale => Err(Failure::Differs.error(format!(
+                "The files {} in {} are out of date.",
+                stale.join(", "),
+                args.output.display()
+            ))),
+        };
+    }
+
+    let message = files.write().map_err(|e| {
+        Failure::Output.error(format!(
+            "Cannot write the files to {}: {}",
+            args.output.display(),
+            e
+        ))
+    })?;
+    if !quiet {
+        println!("{}", message);
+    }
+    Ok(())
+}
+
+/// Generates the code for all machines of a manifest, `jobs` at the same time, and
+/// prints a summary. All machines are generated, and the error is the category of
+/// the first failure in the manifest.
+fn build(
+    manifest: &Path,
+    jobs: Option<usize>,
+    check: bool,
+    format: Option<input::Format>,
+    quiet: bool,
+) -> Result<(), Error> {
+    let mut machines = manifest::read(manifest).map_err(|e| Failure::Input.error(e))?;
+    for args in &mut machines {
+        args.check = check;
+    }
+    let jobs = jobs
+        .or_else(|| thread::available_parallelism().ok().map(|n| n.get()))
+        .unwrap_or(1)
+        .clamp(1, machines.len());
+
+    let next = AtomicUsize::new(0);
+    let results = Mutex::new(machines.iter().map(|_| None).collect::<Vec<_>>());
+    thread::scope(|scope| {
+        for _ in 0..jobs {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(args) = machines.get(index) else {
+                    break;
+                };
+                let error = generate(args, format, true).err();
+                results.lock().unwrap()[index] = error;
+            });
+        }
+    });
+
+    let mut failures = Vec::new();
+    for (args, result) in machines.iter().zip(results.into_inner().unwrap()) {
+        match result {
+            None if !quiet => println!("{} -> {}", args.file.display(), args.output.display()),
+            None => {}
+            Some(error) => {
+                let file = args.file.display().to_string();
+                if error.message.contains(&file) {
+                    eprintln!("{}", error.message);
+                } else {
+                    eprintln!("{}: {}", file, error.message);
+                }
+                failures.push(error.failure);
+            }
+        }
+    }
+
+    match failures.first() {
+        None if !quiet => {
+            println!("{} machines generated.", machines.len());
+            Ok(())
+        }
+        None => Ok(()),
+        Some(failure) => Err(failure.error(format!(
+            "{} of {} machines failed.",
+            failures.len(),
+            machines.len()
+        ))),
+    }
+}
+
+/// How often `watch` looks for changed files.
+const WATCH_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Generates the code like `generate`, and generates it again whenever the machine file
+/// or a file in the template directory changes. Errors are printed, but do not stop it.
+fn watch(args: &GenerateArgs, format: Option<input::Format>, quiet: bool) -> ! {
+    let mut snapshot = Vec::new();
+    loop {
+        let current = modification_times(args);
+        if current != snapshot {
+            if !snapshot.is_empty() && !quiet {
+                println!("Changed, regenerating...");
+            }
+            snapshot = current;
+
+            let diagnostics = diagnose(&args.file, format);
+            eprint!("{}", report::to_text(&diagnostics));
+            if !diagnostics.iter().any(Diagnostic::is_error) {
+                if let Err(error) = generate(args, format, quiet) {
+                    eprintln!("error: {}", error.message);
+                }
+            }
+            if !quiet {
+                println!("Watching {} for changes.", args.file.display());
+            }
+        }
+        thread::sleep(WATCH_INTERVAL);
+    }
+}
+
+/// Returns the watched files with their modification time and size. A file that
+/// cannot be read has neither, so that deleting and recreating it counts as a change.
+fn modification_times(args: &GenerateArgs) -> Vec<(PathBuf, Option<(SystemTime, u64)>)> {
+    let mut paths = vec![args.file.clone()];
+    let mut dirs: Vec<PathBuf> = args.template_dir.iter().cloned().collect();
+    while let Some(dir) = dirs.pop() {
+        for entry in fs::read_dir(&dir).into_iter().flatten().flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                dirs.push(path);
+            } else {
+                paths.push(path);
+            }
+        }
+    }
+    paths.sort();
+
+    paths
+        .into_iter()
+        .map(|path| {
+            let metadata = fs::metadata(&path).ok();
+            let time = metadata.and_then(|m| Some((m.modified().ok()?, m.len())));
+            (path, time)
+        })
+        .collect()
+}
+
+/// Runs all checks and analyses on the machines and prints all findings. Only errors
+/// fail the check, and the error is the category of the first failure.
+fn check(
+    paths: &[PathBuf],
+    output: CheckFormat,
+    format: Option<input::Format>,
+    quiet: bool,
+) -> Result<(), Error> {
+    let mut diagnostics = Vec::new();
+    let mut failures = Vec::new();
+
+    for path in paths {
+        let found = diagnose(path, format);
+        if found.iter().any(|d| d.finding.rule == "parse") {
+            failures.push(Failure::Input);
+        } else if found.iter().any(Diagnostic::is_error) {
+            failures.push(Failure::Invalid);
+        } else if !quiet && output == CheckFormat::Text {
+            println!("{}: ok", path.display());
+        }
+        diagnostics.extend(found);
+    }
+
+    match output {
+        CheckFormat::Text => eprint!("{}", report::to_text(&diagnostics)),
+        CheckFormat::Json => print!("{}", report::to_json(&diagnostics)),
+        CheckFormat::Sarif => print!("{}", report::to_sarif(&diagnostics)),
+    }
+
+    match failures.first() {
+        None => Ok(()),
+        Some(failure) => Err(failure.error(format!(
+            "{} of {} machines failed.",
+            failures.len(),
+            paths.len()
+        ))),
+    }
+}
+
+/// Reads a machine file and returns all findings, or the error if it cannot be read.
+fn diagnose(path: &Path, format: Option<input::Format>) -> Vec<Diagnostic> {
+    let file = path.display().to_string();
+    match read_machine(path, format) {
+        Ok(state_machine) => {
+            let contents = fs::read_to_string(path).unwrap_or_default();
+            checks::find_all(&state_machine)
+                .into_iter()
+                .map(|finding| Diagnostic::new(&file, &contents, finding))
+                .collect()
+        }
+        Err(error) => {
+            let message = error.message.strip_prefix(&format!("{}: ", file));
+            let message = message.unwrap_or(&error.message).to_string();
+            vec![Diagnostic::parse_error(&file, message)]
+        }
+    }
+}
+
+/// Runs the machine with the inputs of each step and prints the transitions and outputs.
+fn simulate(path: &Path, steps: &[String], format: Option<input::Format>) -> Result<(), Error> {
+    let state_machine = read_valid_machine(path, format)?;
+    let mut executor = Executor::new(&state_machine).map_err(|e| Failure::Invalid.error(e))?;
+
+    let steps = if steps.is_empty() {
+        std::io::stdin()
+            .lock()
+            .lines()
+            .collect::<Result<_, _>>()
+            .map_err(|e| Failure::Input.error(e))?
+    } else {
+        steps.to_vec()
+    };
+
+    for step in &steps {
+        let input: HashMap<String, bool> = step
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|symbol| !symbol.is_empty() && *symbol != "-")
+            .map(|symbol| (symbol.to_string(), true))
+            .collect();
+        let from = executor.state().to_string();
+        let result = executor.step(&input).map_err(|e| Failure::Input.error(e))?;
+
+        println!(
+            "{} -> {} outputs: [{}]",
+            from,
+            executor.state(),
+            result.outputs.join(", ")
+        );
+    }
+    Ok(())
+}
+
+/// Prints the machine in an exchange format.
+fn export(
+    path: &Path,
+    export_format: ExportFormat,
+    name: Option<String>,
+    encoding: verilog_generator::Encoding,
+    format: Option<input::Format>,
+) -> Result<(), Error> {
+    let state_machine = read_valid_machine(path, format)?;
+    let name = name_or_file_stem(name, path)?;
+
+    // SCXML, KISS2 and BLIF are rendered with the templates of the generators.
+    let render = || {
+        let language = match export_format {
+            ExportFormat::Scxml => scxml::LANGUAGE,
+            ExportFormat::Kiss2 => kiss::LANGUAGE,
+            _ => kiss::BLIF_LANGUAGE,
+        };
+        let templates = templates::Templates::new(language, None)?;
+        let files = files::Files::new(PathBuf::new());
+        let files = match export_format {
+            ExportFormat::Scxml => scxml::generate(&name, &state_machine, &templates, files),
+            ExportFormat::Kiss2 => kiss::generate(&name, &state_machine, &templates, files),
+            _ => kiss::generate_blif(&name, &state_machine, &templates, encoding, files),
+        }?;
+        Ok::<String, String>(files.contents().map(|(_, content)| content).collect())
     };
 
-    let path = match cli.output {
-        Some(path) => path,
-        None => "./".into(),
+    let document = match export_format {
+        ExportFormat::Yaml => writer::to_yaml(&state_machine),
+        ExportFormat::Json => writer::to_json(&state_machine),
+        ExportFormat::Toml => writer::to_toml(&state_machine),
+        _ => render().map_err(|e| Failure::Generate.error(e))?,
     };
+    print!("{}", document);
+    Ok(())
+}
+
+/// Rewrites the machine files in the canonical YAML layout. With `check`, the files are
+/// not written, and an error lists the files that are not formatted.
+fn format_files(
+    paths: &[PathBuf],
+    check: bool,
+    stdout: bool,
+    format: Option<input::Format>,
+    quiet: bool,
+) -> Result<(), Error> {
+    let mut unformatted = Vec::new();
 
-    let files = files::Files::new(path);
-    let c_files = c_generator::generate(&name, &state_machine, files)?;
+    for path in paths {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| Failure::Input.error(format!("Cannot read {}: {}", path.display(), e)))?;
+        let state_machine = read_machine(path, format)?;
+        let yaml = writer::to_yaml(&state_machine);
 
-    c_files.write()?;
+        if stdout {
+            print!("{}", yaml);
+        } else if format.unwrap_or_else(|| input::Format::from_path(path)) != input::Format::Yaml {
+            return Err(Failure::Input.error(format!(
+                "{} is not a YAML file. Use --stdout to print it as YAML.",
+                path.display()
+            )));
+        } else if yaml != contents {
+            if check {
+                unformatted.push(path.display().to_string());
+            } else {
+                fs::write(path, yaml).map_err(|e| Failure::Output.error(e))?;
+                if !quiet {
+                    println!("Formatted {}", path.display());
+                }
+            }
+        }
+    }
 
+    if !unformatted.is_empty() {
+        return Err(Failure::Differs.error(format!("Not formatted: {}", unformatted.join(", "))));
+    }
     Ok(())
 }
 
-/// Parse the YAML file and return a `MooreMachine` or a `MealyMachine`.
-/// If the YAML file contains both a Moore and a Mealy machine or neither of them, an error is returned.
-fn parse_yaml(yaml_str: &str) -> Result<StateMachine, &'static str> {
-    let moore_machine: Result<MooreMachine, _> = serde_yaml::from_str(&yaml_str);
-    let mealy_machine: Result<MealyMachine, _> = serde_yaml::from_str(&yaml_str);
+/// Prints the changes between two machines. Fails if there are any.
+fn compare(old: &Path, new: &Path, format: Option<input::Format>) -> Result<(), Error> {
+    let changes = diff::diff(&read_machine(old, format)?, &read_machine(new, format)?);
+    for change in &changes {
+        println!("{}", change);
+    }
 
-    match (moore_machine, mealy_machine) {
-        (Ok(moore_machine), Err(_)) => Ok(StateMachine::Moore(moore_machine)),
-        (Err(_), Ok(mealy_machine)) => Ok(StateMachine::Mealy(mealy_machine)),
-        (Err(_), Err(_)) => Err(
-            "The YAML file does not contain a Moore or a Mealy machine. Please check the syntax of the file.",
-        ),
-        (Ok(_), Ok(_)) => Err("The YAML file contains both a Moore and a Mealy machine."),
+    if changes.is_empty() {
+        Ok(())
+    } else {
+        Err(Failure::Differs.error(format!("{} and {} differ.", old.display(), new.display())))
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use input::Format;
+    use state_machine_builder::state_machines::{MealyMachine, MooreMachine, StateMachine};
+
+    #[test]
+    fn test_name_or_file_stem() {
+        let path = Path::new("machines/motor_sm.yaml");
+
+        assert_eq!(name_or_file_stem(None, path).unwrap(), "motor_sm");
+        assert_eq!(
+            name_or_file_stem(Some("motor".into()), path).unwrap(),
+            "motor"
+        );
+
+        let error = name_or_file_stem(None, Path::new("my-machine.yaml")).unwrap_err();
+        assert_eq!(error.failure, Failure::Invalid);
+        assert_eq!(
+            error.message,
+            "my-machine.yaml: The name \"my-machine\" is not an identifier. \
+             Set another name with --name."
+        );
+        assert!(name_or_file_stem(None, Path::new("")).is_err());
+        assert!(name_or_file_stem(Some("1st".into()), path).is_err());
+    }
+
+    #[test]
+    fn test_modification_times() {
+        let dir = std::env::temp_dir().join(format!(
+            "state_machine_builder_watch_{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("templates/moore")).unwrap();
+        fs::write(dir.join("machine.yaml"), "states: []").unwrap();
+        fs::write(dir.join("templates/moore/case_if.c"), "{{ name }}").unwrap();
+        let args = Cli::parse_from([
+            "state_machine_builder",
+            "generate",
+            dir.join("machine.yaml").to_str().unwrap(),
+            "--template-dir",
+            dir.join("templates").to_str().unwrap(),
+            "--watch",
+        ]);
+        let Command::Generate(args) = args.command else {
+            panic!("Expected the generate command");
+        };
+
+        let before = modification_times(&args);
+        assert_eq!(before.len(), 2);
+        assert_eq!(before, modification_times(&args));
+
+        fs::write(dir.join("templates/moore/case_if.c"), "{{ name }} changed").unwrap();
+        assert_ne!(before, modification_times(&args));
+        fs::remove_file(dir.join("machine.yaml")).unwrap();
+        assert_eq!(modification_times(&args)[0].1, None);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_failure_categories() {
+        let missing = read_machine(Path::new("resources/missing.yaml"), None).unwrap_err();
+        let wrong_format =
+            read_machine(Path::new("resources/test_moore.yaml"), Some(Format::Kiss2)).unwrap_err();
+        let checked = |path: &str| check(&[PathBuf::from(path)], CheckFormat::Text, None, true);
+
+        assert_eq!(missing.failure, Failure::Input);
+        assert_eq!(wrong_format.failure, Failure::Input);
+        assert!(checked("resources/test_moore.yaml").is_ok());
+        assert_eq!(
+            checked("resources/missing.yaml").unwrap_err().failure,
+            Failure::Input
+        );
+    }
 
     #[test]
     fn test_parse_moore_yaml() {
         let yaml_str = include_str!("../resources/test_moore.yaml");
-        let moore_machine: Result<MooreMachine, _> = serde_yaml::from_str(&yaml_str);
+        let moore_machine: Result<MooreMachine, _> = serde_yaml::from_str(yaml_str);
 
         assert!(moore_machine.is_ok());
     }
@@ -73,7 +629,7 @@ mod test {
     #[test]
     fn test_parse_not_mealy_yaml() {
         let yaml_str = include_str!("../resources/test_moore.yaml");
-        let mealy_machine: Result<MealyMachine, _> = serde_yaml::from_str(&yaml_str);
+        let mealy_machine: Result<MealyMachine, _> = serde_yaml::from_str(yaml_str);
 
         assert!(mealy_machine.is_err());
     }
@@ -81,7 +637,7 @@ mod test {
     #[test]
     fn test_config_moore() {
         let yaml_str = include_str!("../resources/test_moore.yaml");
-        let state_machine = parse_yaml(&yaml_str).unwrap();
+        let state_machine = input::parse(yaml_str, Format::Yaml).unwrap();
 
         let val = match state_machine {
             StateMachine::Moore(m) => checks::validate_unique_elements(&m),
@@ -94,7 +650,7 @@ mod test {
     #[test]
     fn test_end_states_moore() {
         let yaml_str = include_str!("../resources/test_moore.yaml");
-        let state_machine = parse_yaml(&yaml_str).unwrap();
+        let state_machine = input::parse(yaml_str, Format::Yaml).unwrap();
 
         let val = match state_machine {
             StateMachine::Moore(m) => checks::validate_end_states(&m),
@@ -107,7 +663,7 @@ mod test {
     #[test]
     fn test_validate_legal_variable_name_moore() {
         let yaml_str = include_str!("../resources/test_moore.yaml");
-        let state_machine = parse_yaml(&yaml_str).unwrap();
+        let state_machine = input::parse(yaml_str, Format::Yaml).unwrap();
 
         let val = match state_machine {
             StateMachine::Moore(m) => checks::validate_legal_variable_name(&m),
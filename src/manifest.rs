@@ -0,0 +1,223 @@
+/*!
+This module reads the project manifest `smb.toml` of the `build` subcommand.
+
+The manifest lists the machines of a project with the options of `generate`. The
+options in `[defaults]` apply to all machines that do not set them:
+
+```toml
+[defaults]
+language = "c"
+output = "generated"
+
+[[machine]]
+file = "machines/motor.yaml"
+
+[[machine]]
+file = "machines/door.yaml"
+name = "door_controller"
+language = "cpp"
+hooks = true
+```
+
+Relative paths are relative to the directory of the manifest. Two machines with the same
+output directory, name, and language are rejected, since their files would collide.
+*/
+
+use std::path::{Path, PathBuf};
+
+use clap::ValueEnum;
+use serde::Deserialize;
+
+use crate::argument_parser::{GenerateArgs, Language, StateEncoding};
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Manifest {
+    #[serde(default)]
+    defaults: Options,
+    #[serde(default, rename = "machine")]
+    machines: Vec<Machine>,
+}
+
+/// The options of `generate` that can be set for all machines.
+#[derive(Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Options {
+    output: Option<PathBuf>,
+    language: Option<String>,
+    hooks: Option<bool>,
+    package: Option<String>,
+    encoding: Option<String>,
+    template_dir: Option<PathBuf>,
+}
+
+/// A machine of the manifest with its own options.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Machine {
+    file: PathBuf,
+    name: Option<String>,
+    output: Option<PathBuf>,
+    language: Option<String>,
+    hooks: Option<bool>,
+    package: Option<String>,
+    encoding: Option<String>,
+    template_dir: Option<PathBuf>,
+}
+
+/// Reads a manifest and returns the arguments of `generate` for each machine.
+pub fn read(path: &Path) -> Result<Vec<GenerateArgs>, String> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+    let dir = path.parent().unwrap_or(Path::new(""));
+    parse(&contents, dir).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+/// Parses a manifest. Relative paths are joined to `dir`.
+fn parse(contents: &str, dir: &Path) -> Result<Vec<GenerateArgs>, String> {
+    let manifest: Manifest = toml::from_str(contents).map_err(|e| e.message().to_string())?;
+    if manifest.machines.is_empty() {
+        return Err("The manifest has no machines.".to_string());
+    }
+    let defaults = manifest.defaults;
+
+    let machines = manifest
+        .machines
+        .into_iter()
+        .map(|machine| {
+            let language = match machine.language.or(defaults.language.clone()) {
+                Some(language) => Language::from_str(&language, true)
+                    .map_err(|_| format!("Unknown language {}.", language))?,
+                None => Language::C,
+            };
+            let encoding = match machine.encoding.or(defaults.encoding.clone()) {
+                Some(encoding) => StateEncoding::from_str(&encoding, true)
+                    .map_err(|_| format!("Unknown encoding {}.", encoding))?,
+                None => StateEncoding::Binary,
+            };
+            let output = machine.output.or(defaults.output.clone());
+            let template_dir = machine.template_dir.or(defaults.template_dir.clone());
+
+            Ok(GenerateArgs {
+                file: dir.join(machine.file),
+                output: dir.join(output.unwrap_or_default()),
+                name: machine.name,
+                language,
+                hooks: machine.hooks.or(defaults.hooks).unwrap_or(false),
+                package: machine.package.or(defaults.package.clone()),
+                encoding,
+                template_dir: template_dir.map(|t| dir.join(t)),
+                watch: false,
+                dry_run: false,
+                check: false,
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    // Two machines with the same target would overwrite each other's files.
+    let target = |args: &GenerateArgs| {
+        let name = args
+            .name
+            .clone()
+            .or_else(|| Some(args.file.file_stem()?.to_string_lossy().into_owned()));
+        (args.output.clone(), name, args.language)
+    };
+    for (index, machine) in machines.iter().enumerate() {
+        if let Some(other) = machines[..index]
+            .iter()
+            .find(|m| target(m) == target(machine))
+        {
+            return Err(format!(
+                "The machines {} and {} are generated with the same name and language into {}.",
+                other.file.display(),
+                machine.file.display(),
+                machine.output.display()
+            ));
+        }
+    }
+
+    Ok(machines)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let machines = parse(
+            r#"
+            [defaults]
+            language = "ts"
+            output = "generated"
+
+            [[machine]]
+            file = "motor.yaml"
+
+            [[machine]]
+            file = "/machines/door.yaml"
+            name = "door"
+            language = "sv"
+            output = "rtl"
+            encoding = "one-hot"
+            "#,
+            Path::new("project"),
+        )
+        .unwrap();
+
+        assert_eq!(machines.len(), 2);
+        assert_eq!(machines[0].file, Path::new("project/motor.yaml"));
+        assert_eq!(machines[0].output, Path::new("project/generated"));
+        assert_eq!(machines[0].language, Language::Typescript);
+        assert_eq!(machines[0].name, None);
+        assert_eq!(machines[1].file, Path::new("/machines/door.yaml"));
+        assert_eq!(machines[1].output, Path::new("project/rtl"));
+        assert_eq!(machines[1].language, Language::Systemverilog);
+        assert_eq!(machines[1].encoding, StateEncoding::OneHot);
+    }
+
+    #[test]
+    fn test_parse_different_targets() {
+        let machines = parse(
+            r#"
+            [[machine]]
+            file = "motor.yaml"
+
+            [[machine]]
+            file = "motor.yaml"
+            language = "go"
+
+            [[machine]]
+            file = "motor.yaml"
+            output = "other"
+            "#,
+            Path::new(""),
+        );
+
+        assert_eq!(machines.unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let parse = |contents| parse(contents, Path::new("")).unwrap_err();
+
+        assert_eq!(parse(""), "The manifest has no machines.");
+        assert_eq!(
+            parse("[[machine]]\nfile = \"a.yaml\"\nlanguage = \"cobol\""),
+            "Unknown language cobol."
+        );
+        assert!(parse("[[machine]]\nfile = \"a.yaml\"\nlangauge = \"c\"").contains("langauge"));
+        assert!(parse("[[machine]]\nname = \"a\"").contains("file"));
+
+        let same_stem =
+            "[[machine]]\nfile = \"a/motor.yaml\"\n[[machine]]\nfile = \"b/motor.yaml\"";
+        assert_eq!(
+            parse(same_stem),
+            "The machines a/motor.yaml and b/motor.yaml are generated with the same name \
+             and language into ."
+        );
+        let same_name = "[[machine]]\nfile = \"a.yaml\"\nname = \"m\"\n\
+                         [[machine]]\nfile = \"b.yaml\"\nname = \"m\"";
+        assert!(parse(same_name).starts_with("The machines a.yaml and b.yaml"));
+    }
+}
@@ -0,0 +1,278 @@
+/*!
+The machine model that is exposed to the templates.
+
+The model flattens a Moore or Mealy machine into plain data: states with their
+outputs and outgoing transitions, and transitions with their guards and outputs.
+The code generators render their templates with this model.
+*/
+
+use serde::Serialize;
+
+use crate::state_machines::{
+    Machine, MealyMachine, MooreMachine, StateMachine, VariableType, VariableValue,
+};
+
+/// The kind of the state machine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Kind {
+    Moore,
+    Mealy,
+}
+
+/// A state machine as seen by the templates.
+#[derive(Debug, Serialize)]
+pub struct Model {
+    pub name: String,
+    pub kind: Kind,
+    pub states: Vec<State>,
+    pub inputs: Vec<String>,
+    pub outputs: Vec<String>,
+    pub transitions: Vec<Transition>,
+    pub start_state: String,
+    pub end_states: Vec<String>,
+    pub variables: Vec<Variable>,
+}
+
+/// An extended state variable with its initial value.
+#[derive(Debug, Serialize)]
+pub struct Variable {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub data_type: VariableType,
+    pub initial: String,
+}
+
+/// A state with its outputs (Moore machines only) and its outgoing transitions.
+#[derive(Debug, Serialize)]
+pub struct State {
+    pub name: String,
+    pub index: usize,
+    pub is_start: bool,
+    pub is_end: bool,
+    pub outputs: Vec<String>,
+    pub transitions: Vec<Transition>,
+}
+
+/// A transition. The outputs are only set for Mealy machines.
+#[derive(Debug, Clone, Serialize)]
+pub struct Transition {
+    pub from: String,
+    pub to: String,
+    pub guard: Vec<Guard>,
+    pub outputs: Vec<String>,
+}
+
+/// A single term of a guard. All terms of a guard must hold for the transition to fire.
+/// A read symbol prefixed with `!` is negated.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Guard {
+    pub symbol: String,
+    pub negated: bool,
+}
+
+impl Guard {
+    /// Parses a read symbol like `i0` or `!i0`.
+    pub fn parse(read_symbol: &str) -> Self {
+        match read_symbol.strip_prefix('!') {
+            Some(symbol) => Guard {
+                symbol: symbol.trim().to_string(),
+                negated: true,
+            },
+            None => Guard {
+                symbol: read_symbol.to_string(),
+                negated: false,
+            },
+        }
+    }
+}
+
+impl Transition {
+    /// Checks if all terms of the guard hold for the input.
+    /// `input` returns whether an input symbol is set.
+    pub fn is_enabled(&self, input: impl Fn(&str) -> bool) -> bool {
+        self.guard.iter().all(|g| input(&g.symbol) != g.negated)
+    }
+}
+
+impl State {
+    /// Returns the transition that fires for the input: the first enabled transition.
+    pub fn next(&self, input: impl Fn(&str) -> bool) -> Option<&Transition> {
+        self.transitions.iter().find(|t| t.is_enabled(&input))
+    }
+}
+
+impl Model {
+    /// Creates the model of a state machine. The name is the name of the generated code.
+    pub fn new(name: &str, state_machine: &StateMachine) -> Self {
+        match state_machine {
+            StateMachine::Moore(m) => Self::from_moore(name, m),
+            StateMachine::Mealy(m) => Self::from_mealy(name, m),
+        }
+    }
+
+    fn from_moore(name: &str, m: &MooreMachine) -> Self {
+        let transitions = m
+            .transitions
+            .iter()
+            .map(|t| Transition {
+                from: t.current_state.clone(),
+                to: t.new_state.clone(),
+                guard: t.read_symbol.iter().map(|s| Guard::parse(s)).collect(),
+                outputs: Vec::new(),
+            })
+            .collect::<Vec<Transition>>();
+
+        // TODO Check if only one output function fits the current state.
+        let state_outputs = |state: &str| {
+            m.output_function
+                .iter()
+                .find(|o| o.current_state == state)
+                .map(|o| o.output_symbol.clone())
+                .unwrap_or_default()
+        };
+
+        Self::build(name, Kind::Moore, m, transitions, state_outputs)
+    }
+
+    fn from_mealy(name: &str, m: &MealyMachine) -> Self {
+        let transitions = m
+            .transitions
+            .iter()
+            .map(|t| Transition {
+                from: t.current_state.clone(),
+                to: t.new_state.clone(),
+                guard: t.read_symbol.iter().map(|s| Guard::parse(s)).collect(),
+                // An empty output symbol is a transition without output.
+                outputs: if t.output_symbol.is_empty() {
+                    Vec::new()
+                } else {
+                    vec![t.output_symbol.clone()]
+                },
+            })
+            .collect::<Vec<Transition>>();
+
+        Self::build(name, Kind::Mealy, m, transitions, |_| Vec::new())
+    }
+
+    fn build<T, O>(
+        name: &str,
+        kind: Kind,
+        m: &Machine<T, O>,
+        transitions: Vec<Transition>,
+        state_outputs: impl Fn(&str) -> Vec<String>,
+    ) -> Self {
+        let states = m
+            .states
+            .iter()
+            .enumerate()
+            .map(|(index, state)| State {
+                name: state.clone(),
+                index,
+                is_start: *state == m.start_state,
+                is_end: m.end_states.contains(state),
+                outputs: state_outputs(state),
+                transitions: transitions
+                    .iter()
+                    .filter(|t| t.from == *state)
+                    .cloned()
+                    .collect(),
+            })
+            .collect();
+
+        Model {
+            name: name.to_string(),
+            kind,
+            states,
+            inputs: m.input_alphabet.clone(),
+            outputs: m.output_alphabet.clone(),
+            transitions,
+            start_state: m.start_state.clone(),
+            end_states: m.end_states.clone(),
+            variables: m
+                .variables
+                .iter()
+                .map(|v| Variable {
+                    name: v.name.clone(),
+                    data_type: v.data_type,
+                    initial: match (v.data_type, v.initial) {
+                        // Integer literals of floats are written as float literals.
+                        (VariableType::Float, Some(VariableValue::Int(i))) => {
+                            VariableValue::Float(i as f64)
+                        }
+                        (_, Some(initial)) => initial,
+                        (VariableType::Bool, None) => VariableValue::Bool(false),
+                        (VariableType::Float, None) => VariableValue::Float(0.0),
+                        (VariableType::Int | VariableType::Uint, None) => VariableValue::Int(0),
+                    }
+                    .to_string(),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn create_moore_model() -> Model {
+        let yaml_str = include_str!("../resources/test_moore.yaml");
+        let machine: MooreMachine = serde_yaml::from_str(yaml_str).unwrap();
+        Model::new("test", &StateMachine::Moore(machine))
+    }
+
+    #[test]
+    fn test_guard_parse() {
+        assert_eq!(
+            Guard::parse("i0"),
+            Guard {
+                symbol: "i0".to_string(),
+                negated: false
+            }
+        );
+        assert_eq!(
+            Guard::parse("!i0"),
+            Guard {
+                symbol: "i0".to_string(),
+                negated: true
+            }
+        );
+    }
+
+    #[test]
+    fn test_moore_model_states() {
+        let model = create_moore_model();
+
+        assert_eq!(model.kind, Kind::Moore);
+        assert_eq!(model.states.len(), 3);
+        assert!(model.states[0].is_start);
+        assert!(model.states[2].is_end);
+        assert_eq!(model.states[0].outputs, vec!["o5", "o6"]);
+        assert!(model.states[1].outputs.is_empty());
+    }
+
+    #[test]
+    fn test_moore_model_transitions() {
+        let model = create_moore_model();
+
+        assert_eq!(model.transitions.len(), 2);
+        assert_eq!(model.states[0].transitions.len(), 1);
+        assert_eq!(model.states[0].transitions[0].to, "q2");
+        assert_eq!(model.states[0].transitions[0].guard.len(), 2);
+        assert!(model.states[2].transitions.is_empty());
+    }
+
+    #[test]
+    fn test_next_transition() {
+        let yaml_str = include_str!("../resources/test_mealy.yaml");
+        let machine: MealyMachine = serde_yaml::from_str(yaml_str).unwrap();
+        let model = Model::new("test", &StateMachine::Mealy(machine));
+        let q2 = &model.states[1];
+
+        // The first enabled transition fires.
+        assert_eq!(q2.next(|_| true).unwrap().to, "q3");
+        assert_eq!(q2.next(|_| false).unwrap().to, "q1");
+        assert!(q2.next(|s| s == "i0").is_none());
+    }
+}
@@ -0,0 +1,362 @@
+/*!
+PlantUML reader
+
+This module reads a state machine from a PlantUML state diagram
+(`@startuml ... @enduml`), so that diagrams in design documents can be used directly.
+
+The mapping is:
+- `[*] --> q1` marks the start state and `q3 --> [*]` an end state.
+- `q1 --> q2 : i0 && !i1` is a transition with the guard `[i0, !i1]`. The terms of a
+  guard can also be separated by commas or written in brackets (`[i0, !i1]`).
+- `q1 --> q2 : i0 / o0` is a transition of a Mealy machine with the output `o0`.
+- `q1 : entry / o5, o6` are the outputs of the state `q1` in a Moore machine.
+- `state q1`, `state "Long name" as q1` and other descriptions declare states.
+
+Composite and concurrent states, pseudo states like `<<choice>>` and history have
+no equivalent and are rejected. Notes, comments and styling are ignored.
+*/
+
+use crate::input::{push_unique, Diagram, Transition};
+use crate::state_machines::StateMachine;
+
+/// The start and end marker of a diagram.
+const MARKER: &str = "[*]";
+
+/// Parses a PlantUML state diagram and returns a `MooreMachine` or a `MealyMachine`.
+/// If a transition has an output, the machine is a Mealy machine and every
+/// transition must have at most one output.
+pub fn parse(text: &str) -> Result<StateMachine, String> {
+    let mut states = Vec::new();
+    let mut start_state: Option<String> = None;
+    let mut end_states = Vec::new();
+    let mut state_outputs: Vec<(String, Vec<String>)> = Vec::new();
+    let mut transitions = Vec::new();
+
+    // The end of the block that is skipped, e.g. a multi-line note.
+    let mut skip_until: Option<&str> = None;
+
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        let error = |message: String| format!("Line {}: {}", index + 1, message);
+
+        if let Some(end) = skip_until {
+            if line.starts_with(end) || (end == "'/" && line.ends_with(end)) {
+                skip_until = None;
+            }
+            continue;
+        }
+
+        if line.is_empty() || line.starts_with('\'') || is_ignored(line) {
+            continue;
+        }
+        if line.starts_with("/'") {
+            if !line.ends_with("'/") {
+                skip_until = Some("'/");
+            }
+            continue;
+        }
+        if line.starts_with("note ") || line.starts_with("legend") {
+            if !line.contains(':') {
+                skip_until = Some(if line.starts_with("note") {
+                    "end note"
+                } else {
+                    "endlegend"
+                });
+            }
+            continue;
+        }
+        if line.ends_with('{') || line == "}" {
+            return Err(error("Composite states are not supported.".into()));
+        }
+        if line == "--" || line == "||" {
+            return Err(error("Concurrent states are not supported.".into()));
+        }
+        if line.contains("<<") {
+            return Err(error(format!(
+                "Pseudo states like <<choice>> are not supported: {}",
+                line
+            )));
+        }
+
+        let (head, label) = split_label(line);
+
+        if let Some((from, to)) = parse_arrow(head) {
+            let from = parse_state(from).map_err(error)?;
+            let to = parse_state(to).map_err(error)?;
+            match (from.as_str(), to.as_str()) {
+                (MARKER, MARKER) => {
+                    return Err(error("A transition needs at least one state.".into()))
+                }
+                (MARKER, _) => {
+                    if start_state.as_ref().is_some_and(|start| *start != to) {
+                        return Err(error(format!(
+                            "The machine has more than one start state: {} and {}",
+                            start_state.unwrap(),
+                            to
+                        )));
+                    }
+                    push_unique(&mut states, to.clone());
+                    start_state = Some(to);
+                }
+                (_, MARKER) => {
+                    push_unique(&mut states, from.clone());
+                    push_unique(&mut end_states, from);
+                }
+                _ => {
+                    push_unique(&mut states, from.clone());
+                    push_unique(&mut states, to.clone());
+                    let (guard, outputs) = parse_label(label.unwrap_or("")).map_err(error)?;
+                    transitions.push(Transition {
+                        from,
+                        to,
+                        guard,
+                        outputs,
+                    });
+                }
+            }
+            continue;
+        }
+
+        let state = match head.strip_prefix("state ") {
+            Some(declaration) => parse_declaration(declaration),
+            None => head,
+        };
+        let state = parse_state(state).map_err(error)?;
+        if state == MARKER {
+            return Err(error("[*] can only be used in transitions.".into()));
+        }
+        push_unique(&mut states, state.clone());
+
+        // A description with `entry / ...` holds the outputs of the state.
+        if let Some(outputs) = label.and_then(|label| label.strip_prefix("entry")) {
+            let outputs = outputs.trim_start().strip_prefix('/').ok_or_else(|| {
+                error(format!(
+                    "Expected \"entry / <outputs>\" for the state {}.",
+                    state
+                ))
+            })?;
+            let outputs = parse_symbols(outputs, false).map_err(error)?;
+            match state_outputs.iter_mut().find(|(s, _)| *s == state) {
+                Some((_, existing)) => existing.extend(outputs),
+                None => state_outputs.push((state, outputs)),
+            }
+        }
+    }
+
+    let start_state = start_state.ok_or(
+        "The diagram does not have a start state. Please add a transition like \"[*] --> <state>\".",
+    )?;
+
+    Diagram {
+        states,
+        inputs: Vec::new(),
+        start_state,
+        end_states,
+        state_outputs,
+        transitions,
+        ..Default::default()
+    }
+    .into_machine()
+}
+
+/// Returns true for lines that only change the layout or appearance of the diagram.
+fn is_ignored(line: &str) -> bool {
+    [
+        "@startuml",
+        "@enduml",
+        "skinparam",
+        "hide ",
+        "title ",
+        "scale ",
+        "caption ",
+    ]
+    .iter()
+    .any(|prefix| line.starts_with(prefix))
+        || line.ends_with(" direction")
+}
+
+/// Splits a line at the first colon outside of double quotes into the head and the
+/// description or label, e.g. `state "A: idle" as a : entry / o0`.
+fn split_label(line: &str) -> (&str, Option<&str>) {
+    let mut quoted = false;
+    for (index, c) in line.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            ':' if !quoted => return (line[..index].trim(), Some(line[index + 1..].trim())),
+            _ => {}
+        }
+    }
+    (line, None)
+}
+
+/// Splits `a --> b`, `a -down-> b`, `a -[#red]-> b` or `b <-- a` into the two states.
+fn parse_arrow(head: &str) -> Option<(&str, &str)> {
+    if let Some(arrow) = head.find("->") {
+        let from = &head[..head[..arrow].find('-').unwrap_or(arrow)];
+        return Some((from, &head[arrow + 2..]));
+    }
+    if let Some(arrow) = head.find("<-") {
+        let to = &head[..arrow];
+        let from = &head[head.rfind('-').unwrap() + 1..];
+        return Some((from, to));
+    }
+    None
+}
+
+/// Returns the name of a declared state, e.g. `q1` for `"Long name" as q1` or `q1 as "Long name"`.
+fn parse_declaration(declaration: &str) -> &str {
+    let declaration = declaration.trim();
+    match declaration.strip_prefix('"') {
+        Some(rest) => match rest.split_once('"') {
+            Some((_, alias)) => alias.trim().trim_start_matches("as").trim(),
+            None => declaration,
+        },
+        None => declaration.split_whitespace().next().unwrap_or(declaration),
+    }
+}
+
+/// Checks the name of a state.
+fn parse_state(state: &str) -> Result<String, String> {
+    let state = state.trim();
+    if state == MARKER || is_symbol(state) {
+        Ok(state.to_string())
+    } else {
+        Err(format!("Invalid state name \"{}\".", state))
+    }
+}
+
+/// Parses the label of a transition (`guard` or `guard / output`).
+fn parse_label(label: &str) -> Result<(Vec<String>, Vec<String>), String> {
+    let (guard, outputs) = match label.split_once('/') {
+        Some((guard, outputs)) => (guard, parse_symbols(outputs, false)?),
+        None => (label, Vec::new()),
+    };
+    let guard = guard.replace("&&", " ").replace(['[', ']'], " ");
+    Ok((parse_symbols(&guard, true)?, outputs))
+}
+
+/// Parses symbols separated by commas or spaces. Guard terms can be negated with `!`.
+fn parse_symbols(text: &str, negation: bool) -> Result<Vec<String>, String> {
+    text.split([',', ' '])
+        .filter(|symbol| !symbol.is_empty())
+        .map(|symbol| {
+            let name = if negation {
+                symbol.strip_prefix('!').unwrap_or(symbol)
+            } else {
+                symbol
+            };
+            if is_symbol(name) {
+                Ok(symbol.to_string())
+            } else {
+                Err(format!("Invalid symbol \"{}\".", symbol))
+            }
+        })
+        .collect()
+}
+
+fn is_symbol(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::model::Model;
+    use crate::test_support::parse as parse_yaml;
+
+    #[test]
+    fn test_parse_moore() {
+        let diagram = include_str!("../resources/test_moore.puml");
+        let original = Model::new(
+            "test",
+            &parse_yaml(include_str!("../resources/test_moore.yaml")),
+        );
+        let model = Model::new("test", &parse(diagram).unwrap());
+
+        assert_eq!(
+            format!("{:?}", model.states),
+            format!("{:?}", original.states)
+        );
+        assert_eq!(model.inputs, original.inputs);
+        assert_eq!(model.outputs, original.outputs);
+        assert_eq!(model.start_state, "q1");
+        assert_eq!(model.end_states, ["q3"]);
+    }
+
+    #[test]
+    fn test_parse_mealy() {
+        let diagram = "@startuml
+[*] --> q1
+q1 -right-> q2 : [i0, !i1] / o0
+q2 -[#red]-> q3 : i1 / o1
+q1 <-- q2 : !i0 / o0
+q3 --> [*]
+@enduml";
+        let original = Model::new(
+            "test",
+            &parse_yaml(include_str!("../resources/test_mealy.yaml")),
+        );
+        let model = Model::new("test", &parse(diagram).unwrap());
+
+        assert_eq!(model.kind, original.kind);
+        assert_eq!(
+            format!("{:?}", model.states),
+            format!("{:?}", original.states)
+        );
+        assert_eq!(model.outputs, original.outputs);
+    }
+
+    #[test]
+    fn test_parse_declarations_and_notes() {
+        let diagram = "@startuml
+hide empty description
+state \"Waiting for input\" as idle
+state done : the end
+note right of idle
+  q1 --> q2
+end note
+' idle --> done
+[*] --> idle
+idle --> done : start && ready
+@enduml";
+        let machine = match parse(diagram).unwrap() {
+            StateMachine::Moore(m) => m,
+            _ => panic!("Wrong state machine type."),
+        };
+
+        assert_eq!(machine.states, ["idle", "done"]);
+        assert_eq!(machine.input_alphabet, ["start", "ready"]);
+        assert_eq!(machine.transitions.len(), 1);
+        assert!(machine.end_states.is_empty());
+    }
+
+    #[test]
+    fn test_parse_quoted_names_with_colons() {
+        let diagram = "state \"A: idle\" as a
+state b as \"B: busy\" : entry / o0
+[*] --> a
+a --> b : go";
+        let machine = match parse(diagram).unwrap() {
+            StateMachine::Moore(m) => m,
+            _ => panic!("Wrong state machine type."),
+        };
+
+        assert_eq!(machine.states, ["a", "b"]);
+        assert_eq!(machine.output_function[0].current_state, "b");
+        assert_eq!(machine.output_function[0].output_symbol, ["o0"]);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let composite = "[*] --> a\nstate a {\n}";
+        let choice = "[*] --> a\nstate c <<choice>>";
+        let symbol = "[*] --> a\na --> b : i0 || i1";
+        let mixed = "[*] --> a\na : entry / o0\na --> b : i0 / o1";
+
+        assert!(parse(composite).unwrap_err().contains("Line 2: Composite"));
+        assert!(parse(choice).unwrap_err().contains("<<choice>>"));
+        assert!(parse(symbol).unwrap_err().contains("Invalid symbol \"||\""));
+        assert!(parse(mixed).unwrap_err().contains("either Moore or Mealy"));
+        assert!(parse("a --> b").unwrap_err().contains("start state"));
+    }
+}
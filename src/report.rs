@@ -0,0 +1,225 @@
+/*!
+Reports of check findings
+
+This module turns the findings of `checks::find_all` into diagnostics with a file and
+a line, and prints them as text for humans, as JSON, or as SARIF 2.1.0 for code
+scanning tools that annotate pull requests.
+*/
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::checks::{Finding, Severity, RULES};
+
+/// The rule of files that cannot be read or parsed.
+const PARSE_RULE: (&str, &str) = (
+    "parse",
+    "The machine file must be readable and valid for its format.",
+);
+
+/// A finding in a machine file.
+#[derive(Debug, Clone, Serialize)]
+pub struct Diagnostic {
+    pub file: String,
+    /// The line of the element of the finding, starting at 1.
+    pub line: usize,
+    #[serde(flatten)]
+    pub finding: Finding,
+}
+
+impl Diagnostic {
+    /// Creates a diagnostic on the first line of `contents` that contains the element
+    /// of the finding as a whole word, or on the first line.
+    pub fn new(file: &str, contents: &str, finding: Finding) -> Self {
+        let line = finding
+            .element
+            .as_deref()
+            .and_then(|element| find_line(contents, element))
+            .unwrap_or(1);
+        Diagnostic {
+            file: file.to_string(),
+            line,
+            finding,
+        }
+    }
+
+    /// Creates a diagnostic for a file that cannot be read or parsed.
+    pub fn parse_error(file: &str, message: String) -> Self {
+        Diagnostic {
+            file: file.to_string(),
+            line: 1,
+            finding: Finding {
+                rule: PARSE_RULE.0,
+                severity: Severity::Error,
+                message,
+                element: None,
+            },
+        }
+    }
+
+    /// Returns whether the diagnostic is an error.
+    pub fn is_error(&self) -> bool {
+        self.finding.severity == Severity::Error
+    }
+}
+
+/// Returns the number of the first line that contains `word` as a whole word.
+fn find_line(contents: &str, word: &str) -> Option<usize> {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    contents
+        .lines()
+        .position(|line| {
+            line.match_indices(word).any(|(start, _)| {
+                let before = line[..start].chars().next_back();
+                let after = line[start + word.len()..].chars().next();
+                !before.is_some_and(is_word) && !after.is_some_and(is_word)
+            })
+        })
+        .map(|index| index + 1)
+}
+
+/// Returns the diagnostics as `file:line: severity[rule]: message`, one per line.
+pub fn to_text(diagnostics: &[Diagnostic]) -> String {
+    diagnostics
+        .iter()
+        .map(|d| {
+            format!(
+                "{}:{}: {}[{}]: {}\n",
+                d.file,
+                d.line,
+                severity_name(d.finding.severity),
+                d.finding.rule,
+                d.finding.message
+            )
+        })
+        .collect()
+}
+
+/// Returns the diagnostics as a JSON array.
+pub fn to_json(diagnostics: &[Diagnostic]) -> String {
+    serde_json::to_string_pretty(diagnostics).unwrap() + "\n"
+}
+
+/// Returns the diagnostics as a SARIF 2.1.0 log with one run.
+pub fn to_sarif(diagnostics: &[Diagnostic]) -> String {
+    let rules: Vec<Value> = RULES
+        .iter()
+        .map(|rule| (rule.id, rule.description, rule.severity))
+        .chain([(PARSE_RULE.0, PARSE_RULE.1, Severity::Error)])
+        .map(|(id, description, severity)| {
+            json!({
+                "id": id,
+                "shortDescription": { "text": description },
+                "defaultConfiguration": { "level": severity_name(severity) }
+            })
+        })
+        .collect();
+    let results: Vec<Value> = diagnostics
+        .iter()
+        .map(|d| {
+            json!({
+                "ruleId": d.finding.rule,
+                "level": severity_name(d.finding.severity),
+                "message": { "text": d.finding.message },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": d.file.replace('\\', "/") },
+                        "region": { "startLine": d.line }
+                    }
+                }]
+            })
+        })
+        .collect();
+
+    let log = json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "State Machine Builder",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules
+                }
+            },
+            "results": results
+        }]
+    });
+    serde_json::to_string_pretty(&log).unwrap() + "\n"
+}
+
+/// Returns the name of the severity, which is also its SARIF level.
+fn severity_name(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::checks::find_all;
+    use crate::test_support::parse;
+
+    fn diagnostics() -> Vec<Diagnostic> {
+        let contents = include_str!("../resources/test_moore.yaml").replace(
+            "end_states:\n  - \"q3\"",
+            "end_states:\n  - \"q3\"\n  - \"q30\"",
+        );
+        let mut diagnostics: Vec<_> = find_all(&parse(&contents))
+            .into_iter()
+            .map(|finding| Diagnostic::new("machines/test.yaml", &contents, finding))
+            .collect();
+        diagnostics.push(Diagnostic::parse_error("bad.yaml", "Bad.".to_string()));
+        diagnostics
+    }
+
+    #[test]
+    fn test_find_line() {
+        let contents = "states:\n  - \"q10\"\n  - \"q1\"\n";
+
+        assert_eq!(find_line(contents, "q1"), Some(3));
+        assert_eq!(find_line(contents, "q2"), None);
+    }
+
+    #[test]
+    fn test_illegal_name_line() {
+        let contents = include_str!("../resources/test_moore.yaml").replace("\"i1\"", "\"1i\"");
+        let finding = find_all(&parse(&contents)).remove(0);
+        let diagnostic = Diagnostic::new("test.yaml", &contents, finding);
+
+        assert_eq!(diagnostic.finding.rule, "legal-names");
+        assert_eq!(diagnostic.line, 7);
+    }
+
+    #[test]
+    fn test_to_text() {
+        let text = to_text(&diagnostics());
+
+        assert!(text.starts_with(
+            "machines/test.yaml:21: error[end-states]: The end state q30 is not a state.\n"
+        ));
+        assert!(text.ends_with("bad.yaml:1: error[parse]: Bad.\n"));
+    }
+
+    #[test]
+    fn test_to_json_and_sarif() {
+        let diagnostics = diagnostics();
+        let json: Value = serde_json::from_str(&to_json(&diagnostics)).unwrap();
+        let sarif: Value = serde_json::from_str(&to_sarif(&diagnostics)).unwrap();
+
+        assert_eq!(json[0]["rule"], "end-states");
+        assert_eq!(json[0]["element"], "q30");
+        assert_eq!(sarif["version"], "2.1.0");
+        let run = &sarif["runs"][0];
+        let rules = run["tool"]["driver"]["rules"].as_array().unwrap();
+        assert_eq!(rules.len(), RULES.len() + 1);
+        assert_eq!(rules[0]["id"], "legal-names");
+        assert_eq!(run["results"][1]["ruleId"], "parse");
+        assert_eq!(
+            run["results"][0]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+            "machines/test.yaml"
+        );
+    }
+}
@@ -0,0 +1,153 @@
+/*!
+Rust code generator
+
+This module generates a Rust module from a state machine. The module contains the
+`State` enum, the `Input` and `Output` structs, and a `Machine` type with `step()`.
+The file has no inner attributes, so it can be included with `include!`.
+
+The generated code is written to a file or directory.
+*/
+
+use crate::files::Files;
+use crate::model::Model;
+use crate::state_machines::StateMachine;
+use crate::templates::Templates;
+
+/// The language of the templates in `resources/templates`.
+pub const LANGUAGE: &str = "rust";
+
+/// Generates the Rust module for the state machine. The code is written to a file or directory.
+/// The name is used for the file and module name.
+pub fn generate(
+    name: &str,
+    state_machine: &StateMachine,
+    templates: &Templates,
+    mut files: Files,
+) -> Result<Files, String> {
+    files.add_file(
+        format!("{}.rs", name),
+        render(name, state_machine, templates)?,
+    );
+
+    Ok(files)
+}
+
+/// Returns the Rust module for the state machine, e.g. for a procedural macro.
+pub fn render(
+    name: &str,
+    state_machine: &StateMachine,
+    templates: &Templates,
+) -> Result<String, String> {
+    templates.render("machine.rs", &Model::new(name, state_machine))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_support::{parse, run_tool, temp_dir};
+    use std::process::Command;
+
+    /// Includes the generated module into the program, compiles it with all warnings as
+    /// errors and runs it.
+    fn assert_runs(name: &str, yaml_str: &str, main: &str) {
+        let dir = temp_dir(&format!("rust_{}", name));
+        let templates = Templates::new(LANGUAGE, None).unwrap();
+        let files = generate(name, &parse(yaml_str), &templates, Files::new(dir.clone())).unwrap();
+        files.write().unwrap();
+
+        let source = dir.join("main.rs");
+        let executable = dir.join("main");
+        std::fs::write(&source, format!("include!(\"{}.rs\");\n\n{}", name, main)).unwrap();
+        let args = ["--edition", "2021", "-D", "warnings"];
+        let paths = [source.to_str().unwrap(), "-o", executable.to_str().unwrap()];
+        run_tool("RUSTC", "rustc", &[&args[..], &paths[..]].concat());
+
+        let status = Command::new(&executable).status().unwrap();
+        assert_eq!(status.code(), Some(0));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_moore_module() {
+        let templates = Templates::new(LANGUAGE, None).unwrap();
+        let model = Model::new(
+            "motor_sm",
+            &parse(include_str!("../resources/test_moore.yaml")),
+        );
+        let source = templates.render("machine.rs", &model).unwrap();
+
+        assert!(source.contains("pub mod motor_sm {"));
+        assert!(source.contains(
+            "State::Q1 => {\n                    output.o5 = true;\n                    output.o6 = true;\n                    if input.i0 && input.i1 {"
+        ));
+        assert!(source.contains("State::Q3 => {}"));
+        assert!(source.contains("matches!(self.state, State::Q3)"));
+        assert!(!source.contains("#!"));
+    }
+
+    #[test]
+    fn test_run_moore() {
+        assert_runs(
+            "moore",
+            include_str!("../resources/test_moore.yaml"),
+            "use moore::{Input, Machine, State};
+
+fn main() {
+    let mut machine = Machine::new();
+    let output = machine.step(Input { i0: true, i1: true });
+    assert!(output.o5 && output.o6);
+    assert_eq!(machine.state(), State::Q2);
+    assert_eq!(machine.previous_state(), State::Q1);
+
+    machine.step(Input { i0: false, i1: true });
+    assert!(machine.is_end_state());
+    assert_eq!(machine.state().name(), \"q3\");
+
+    machine.reset();
+    assert_eq!(machine.state(), Machine::START_STATE);
+}
+",
+        );
+    }
+
+    #[test]
+    fn test_run_mealy_with_variables() {
+        let yaml_str = format!(
+            "{}variables:
+  - name: counter
+    type: uint
+  - name: gain
+    type: float
+    initial: 2
+",
+            include_str!("../resources/test_mealy.yaml")
+        );
+
+        assert_runs(
+            "mealy",
+            &yaml_str,
+            "use mealy::{Input, Machine, State};
+
+fn main() {
+    let mut machine = Machine::default();
+    assert_eq!(machine.gain, 2.0);
+    machine.counter += 1;
+
+    let output = machine.step(Input { i0: true, i1: false });
+    assert!(output.o0 && !output.o1);
+    assert_eq!(machine.state(), State::Q2);
+
+    // The first matching transition fires.
+    let output = machine.step(Input { i0: false, i1: true });
+    assert!(output.o1 && !output.o0);
+    assert_eq!(machine.state(), State::Q3);
+
+    // No transition fires in an end state: no outputs.
+    assert_eq!(machine.step(Input::default()), Default::default());
+    assert_eq!(State::ALL.len(), 3);
+}
+",
+        );
+    }
+}
@@ -0,0 +1,363 @@
+/*!
+SCXML reader and writer
+
+This module converts between state machines and W3C SCXML documents, so that
+machines can be exchanged with statechart tools.
+
+The mapping is:
+- `<state>` and `<final>` are the states; `<final>` states are the end states.
+- The `initial` attribute is the start state (default: the first state).
+- `<data>` entries of the `<datamodel>` are the inputs.
+- `<transition cond="a && !b" target="q">` is a transition with the guard `[a, !b]`.
+  A transition `event` is read as an additional input of the guard.
+- `<raise event="o">` in `<onentry>` is a Moore output, inside `<transition>` a Mealy output.
+- `<smb:output event="o">` in the namespace `NAMESPACE` declares an output, so that the
+  outputs keep their order and outputs that are never raised are not lost. Documents
+  without these elements have the raised events as outputs.
+
+Nested states, `<parallel>` and `<history>` have no equivalent and are rejected.
+*/
+
+use roxmltree::{Document, Node};
+
+use minijinja::{context, Value};
+
+use crate::files::Files;
+use crate::input::{push_unique, Diagram, Transition};
+use crate::model::Model;
+use crate::state_machines::StateMachine;
+use crate::templates::Templates;
+
+/// The language of the templates in `resources/templates`.
+pub const LANGUAGE: &str = "scxml";
+
+/// The namespace of the elements that SCXML has no equivalent for, e.g. the outputs.
+const NAMESPACE: &str = "urn:state-machine-builder";
+
+/// Generates the SCXML document for the state machine. The document is written to a file or directory.
+/// The name is used for the file name.
+/// The templates are taken from `templates`.
+pub fn generate(
+    name: &str,
+    state_machine: &StateMachine,
+    templates: &Templates,
+    mut files: Files,
+) -> Result<Files, String> {
+    let model = Model::new(name, state_machine);
+
+    // A <final> state cannot have transitions.
+    if let Some(state) = model
+        .states
+        .iter()
+        .find(|state| state.is_end && !state.transitions.is_empty())
+    {
+        return Err(format!(
+            "The end state {} has outgoing transitions, which SCXML does not allow.",
+            state.name
+        ));
+    }
+
+    files.add_file(
+        format!("{}.scxml", name),
+        templates.render("machine.scxml", &create_context(&model))?,
+    );
+
+    Ok(files)
+}
+
+/// Creates the template context: the model with the namespace of the outputs.
+fn create_context(model: &Model) -> Value {
+    context! {
+        namespace => NAMESPACE,
+        ..Value::from_serialize(model)
+    }
+}
+
+/// Parses an SCXML document and returns a `MooreMachine` or a `MealyMachine`.
+/// If a transition raises an event, the machine is a Mealy machine and every
+/// transition must raise at most one event.
+pub fn parse(xml: &str) -> Result<StateMachine, String> {
+    let document = Document::parse(xml).map_err(|e| format!("Invalid SCXML document: {}", e))?;
+    let root = document.root_element();
+    if root.tag_name().name() != "scxml" {
+        return Err(format!(
+            "Expected an <scxml> root element, found <{}>.",
+            root.tag_name().name()
+        ));
+    }
+
+    let mut states = Vec::new();
+    let mut end_states = Vec::new();
+    let mut inputs = Vec::new();
+    let mut outputs = Vec::new();
+    let mut state_outputs = Vec::new();
+    let mut transitions = Vec::new();
+
+    for node in root.children().filter(Node::is_element) {
+        match node.tag_name().name() {
+            "output" if node.tag_name().namespace() == Some(NAMESPACE) => {
+                push_unique(&mut outputs, attribute(&document, node, "event")?);
+            }
+            "datamodel" => {
+                for data in node.children().filter(|n| n.has_tag_name("data")) {
+                    push_unique(&mut inputs, attribute(&document, data, "id")?);
+                }
+            }
+            "state" | "final" => {
+                let state = attribute(&document, node, "id")?;
+                if node.has_tag_name("final") {
+                    end_states.push(state.clone());
+                }
+                for child in node.children().filter(Node::is_element) {
+                    match child.tag_name().name() {
+                        "onentry" => {
+                            let raised = raised_events(&document, child)?;
+                            state_outputs.push((state.clone(), raised));
+                        }
+                        "transition" => {
+                            transitions.push(parse_transition(&document, &state, child)?)
+                        }
+                        _ => return Err(unsupported(&document, child)),
+                    }
+                }
+                states.push(state);
+            }
+            _ => return Err(unsupported(&document, node)),
+        }
+    }
+
+    let start_state = match root.attribute("initial") {
+        Some(initial) => initial.to_string(),
+        None => states
+            .first()
+            .cloned()
+            .ok_or("The SCXML document does not contain any states.")?,
+    };
+
+    Diagram {
+        states,
+        inputs,
+        outputs,
+        start_state,
+        end_states,
+        state_outputs,
+        transitions,
+        ..Default::default()
+    }
+    .into_machine()
+}
+
+/// Reads a `<transition>` of the state `from`.
+fn parse_transition(document: &Document, from: &str, node: Node) -> Result<Transition, String> {
+    let to = attribute(document, node, "target")?;
+    if to.split_whitespace().count() != 1 {
+        return Err(format!(
+            "The transition from {} to \"{}\" must have exactly one target.",
+            from, to
+        ));
+    }
+
+    let mut guard = Vec::new();
+    if let Some(event) = node.attribute("event") {
+        if event.split_whitespace().count() != 1 {
+            return Err(format!(
+                "The transition from {} to {} must have at most one event.",
+                from, to
+            ));
+        }
+        guard.push(event.to_string());
+    }
+    if let Some(cond) = node.attribute("cond") {
+        guard.extend(parse_cond(cond)?);
+    }
+
+    Ok(Transition {
+        from: from.to_string(),
+        to,
+        guard,
+        outputs: raised_events(document, node)?,
+    })
+}
+
+/// Parses a condition of the form `a && !b` into guard terms. `true` is the empty guard.
+fn parse_cond(cond: &str) -> Result<Vec<String>, String> {
+    let mut guard = Vec::new();
+    for term in cond.split("&&").map(str::trim) {
+        let symbol = term.strip_prefix('!').unwrap_or(term).trim();
+        if term == "true" {
+            continue;
+        }
+        if symbol.is_empty() || !symbol.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            return Err(format!(
+                "Unsupported condition \"{}\". Only inputs combined with && and ! are supported.",
+                cond
+            ));
+        }
+        guard.push(if term.starts_with('!') {
+            format!("!{}", symbol)
+        } else {
+            symbol.to_string()
+        });
+    }
+    Ok(guard)
+}
+
+/// Returns the events raised by the `<raise>` children of a node.
+fn raised_events(document: &Document, node: Node) -> Result<Vec<String>, String> {
+    node.children()
+        .filter(Node::is_element)
+        .map(|child| match child.tag_name().name() {
+            "raise" => attribute(document, child, "event"),
+            _ => Err(unsupported(document, child)),
+        })
+        .collect()
+}
+
+/// Returns a required attribute of a node.
+fn attribute(document: &Document, node: Node, name: &str) -> Result<String, String> {
+    node.attribute(name).map(str::to_string).ok_or_else(|| {
+        format!(
+            "The element <{}> at {} has no {} attribute.",
+            node.tag_name().name(),
+            document.text_pos_at(node.range().start),
+            name
+        )
+    })
+}
+
+/// Returns the error for an element that has no equivalent in a state machine.
+fn unsupported(document: &Document, node: Node) -> String {
+    format!(
+        "The element <{}> at {} is not supported.",
+        node.tag_name().name(),
+        document.text_pos_at(node.range().start)
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::diff::diff;
+    use crate::test_support::parse as parse_yaml;
+
+    fn export(yaml_str: &str) -> String {
+        let templates = Templates::new(LANGUAGE, None).unwrap();
+        let model = Model::new("test", &parse_yaml(yaml_str));
+        templates
+            .render("machine.scxml", &create_context(&model))
+            .unwrap()
+    }
+
+    /// Exports the machine and checks that it is read back without changes.
+    fn assert_round_trip(yaml_str: &str) -> String {
+        let scxml = export(yaml_str);
+        let (original, machine) = (parse_yaml(yaml_str), parse(&scxml).unwrap());
+        let changes = diff(&original, &machine);
+        assert!(changes.is_empty(), "{:?}", changes);
+
+        // The diff does not compare the order of the symbols.
+        let (original, model) = (Model::new("test", &original), Model::new("test", &machine));
+        assert_eq!(model.inputs, original.inputs);
+        assert_eq!(model.outputs, original.outputs);
+        scxml
+    }
+
+    #[test]
+    fn test_export_moore() {
+        let scxml = export(include_str!("../resources/test_moore.yaml"));
+
+        assert!(scxml.contains("name=\"test\" initial=\"q1\""));
+        assert!(scxml.contains("<data id=\"i0\" expr=\"false\"/>"));
+        assert!(scxml.contains(
+            "<onentry>\n            <raise event=\"o5\"/>\n            <raise event=\"o6\"/>\n        </onentry>"
+        ));
+        assert!(scxml.contains("<transition cond=\"i0 &amp;&amp; i1\" target=\"q2\"/>"));
+        assert!(scxml.contains("<final id=\"q3\""));
+    }
+
+    #[test]
+    fn test_round_trip_moore() {
+        let scxml = assert_round_trip(include_str!("../resources/test_moore.yaml"));
+
+        assert!(scxml.contains("xmlns:smb=\"urn:state-machine-builder\""));
+        assert!(scxml.contains("    <smb:output event=\"o5\"/>\n    <smb:output event=\"o6\"/>\n"));
+    }
+
+    #[test]
+    fn test_round_trip_unused_output() {
+        // o7 is never raised, and o6 comes before o5 in the output alphabet.
+        let yaml_str = include_str!("../resources/test_moore.yaml").replace(
+            "  - \"o5\"\n  - \"o6\"",
+            "  - \"o6\"\n  - \"o5\"\n  - \"o7\"",
+        );
+        let scxml = assert_round_trip(&yaml_str);
+
+        let StateMachine::Moore(machine) = parse(&scxml).unwrap() else {
+            panic!("Wrong state machine type.");
+        };
+        assert_eq!(machine.output_alphabet, ["o6", "o5", "o7"]);
+    }
+
+    #[test]
+    fn test_round_trip_mealy() {
+        let scxml = assert_round_trip(include_str!("../resources/test_mealy.yaml"));
+
+        assert!(scxml.contains(
+            "<transition cond=\"!i0\" target=\"q1\">\n            <raise event=\"o0\"/>"
+        ));
+    }
+
+    #[test]
+    fn test_parse_events_and_defaults() {
+        let scxml = r#"<scxml xmlns="http://www.w3.org/2005/07/scxml" version="1.0">
+    <state id="idle">
+        <transition event="start" cond="ready &amp;&amp; !fault" target="running"/>
+    </state>
+    <state id="running">
+        <transition cond="true" target="done"/>
+    </state>
+    <final id="done"/>
+</scxml>"#;
+
+        let machine = match parse(scxml).unwrap() {
+            StateMachine::Moore(m) => m,
+            _ => panic!("Wrong state machine type."),
+        };
+
+        assert_eq!(machine.start_state, "idle");
+        assert_eq!(machine.end_states, vec!["done"]);
+        assert_eq!(machine.input_alphabet, vec!["start", "ready", "fault"]);
+        assert_eq!(
+            machine.transitions[0].read_symbol,
+            vec!["start", "ready", "!fault"]
+        );
+        assert!(machine.transitions[1].read_symbol.is_empty());
+    }
+
+    #[test]
+    fn test_parse_unsupported() {
+        let nested = r#"<scxml><state id="a"><state id="b"/></state></scxml>"#;
+        let parallel = r#"<scxml><parallel id="p"/></scxml>"#;
+        let cond = r#"<scxml><state id="a"><transition cond="x || y" target="a"/></state></scxml>"#;
+
+        assert!(parse(nested).unwrap_err().contains("<state>"));
+        assert!(parse(parallel).unwrap_err().contains("<parallel>"));
+        assert!(parse(cond).unwrap_err().contains("Unsupported condition"));
+        assert!(parse("<machine/>").is_err());
+    }
+
+    #[test]
+    fn test_export_end_state_with_transitions() {
+        let yaml_str = include_str!("../resources/test_moore.yaml")
+            .replace("end_states:\n  - \"q3\"", "end_states:\n  - \"q2\"");
+        let templates = Templates::new(LANGUAGE, None).unwrap();
+        let result = generate(
+            "test",
+            &parse_yaml(&yaml_str),
+            &templates,
+            Files::new("./".into()),
+        );
+
+        assert!(result.is_err());
+    }
+}
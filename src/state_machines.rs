@@ -1,29 +1,82 @@
-use serde::Deserialize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 
 pub type MooreMachine = Machine<MooreTransition, Vec<MooreOutputFunction>>;
 pub type MealyMachine = Machine<MealyTransition, Option<()>>;
 
 /// Represents a finite state machine. It can be either a Moore or a Mealy machine.
-#[derive(Debug)]
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(untagged)]
+#[schemars(untagged)]
 pub enum StateMachine {
     Moore(MooreMachine),
     Mealy(MealyMachine),
 }
 
 /// Represents a generic state machine.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+#[schemars(rename = "Machine_{T}")]
 pub struct Machine<T, M> {
-    pub states: Vec<String>,          // Q
-    pub input_alphabet: Vec<String>,  // Σ
+    /// The names of the states.
+    pub states: Vec<String>, // Q
+    /// The input symbols. They can be used in the read symbols of the transitions.
+    pub input_alphabet: Vec<String>, // Σ
+    /// The output symbols.
     pub output_alphabet: Vec<String>, // Ω
-    pub transitions: Vec<T>,          // δ
-    pub output_function: M,           // λ (moore) or None (mealy machine)
-    pub start_state: String,          // q0
-    pub end_states: Vec<String>,      // F
+    /// The transitions. The transitions of a state are checked in order.
+    pub transitions: Vec<T>, // δ
+    /// The outputs of the states (Moore machines only).
+    pub output_function: M, // λ (moore) or None (mealy machine)
+    /// The state the machine starts in.
+    pub start_state: String, // q0
+    /// The states that end the machine.
+    pub end_states: Vec<String>, // F
+    /// Extended state variables that are stored next to the state.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub variables: Vec<Variable>, // Extended state variables
+}
+
+/// Represents an extended state variable. It is stored next to the state and reset with it.
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct Variable {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub data_type: VariableType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub initial: Option<VariableValue>, // Default: false or 0
+}
+
+/// The data type of an extended state variable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum VariableType {
+    Bool,
+    Int,
+    Uint,
+    Float,
+}
+
+/// The initial value of an extended state variable.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize, JsonSchema)]
+#[serde(untagged)]
+pub enum VariableValue {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+}
+
+impl std::fmt::Display for VariableValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VariableValue::Bool(b) => write!(f, "{}", b),
+            VariableValue::Int(i) => write!(f, "{}", i),
+            VariableValue::Float(x) => write!(f, "{:?}", x),
+        }
+    }
 }
 
 /// Represents a transition in a Moore machine.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
 pub struct MooreTransition {
     pub current_state: String,    // q
     pub read_symbol: Vec<String>, // s
@@ -31,14 +84,14 @@ pub struct MooreTransition {
 }
 
 /// Represents an output function in a Moore machine.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
 pub struct MooreOutputFunction {
-    pub current_state: String, // q
+    pub current_state: String,      // q
     pub output_symbol: Vec<String>, // o
 }
 
 /// Represents a transition in a Mealy machine.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
 pub struct MealyTransition {
     pub current_state: String,    // q
     pub read_symbol: Vec<String>, // s
@@ -0,0 +1,379 @@
+/*!
+Template engine for the code generators.
+
+The templates are Jinja templates rendered with [MiniJinja](https://docs.rs/minijinja).
+The default templates are compiled into the binary. A template directory can replace
+any of them. The templates are rendered with a [`Model`](crate::model::Model) of the state machine.
+
+Besides the builtin filters, the following filters are available:
+
+* `snake_case` - `MotorControl` → `motor_control`
+* `upper_snake_case` - `MotorControl` → `MOTOR_CONTROL`
+* `camel_case` - `motor_control` → `motorControl`
+* `pascal_case` - `motor_control` → `MotorControl`
+* `ljust(width)` - pads the string with spaces to the given width
+*/
+
+use std::path::{Path, PathBuf};
+
+use heck::{ToLowerCamelCase, ToShoutySnakeCase, ToSnakeCase, ToUpperCamelCase};
+use minijinja::{Environment, UndefinedBehavior};
+use serde::Serialize;
+
+/// The built-in templates. The names are `<language>/<template>`.
+const BUILTIN_TEMPLATES: &[(&str, &str)] = &[
+    (
+        "c/header.h",
+        include_str!("../resources/templates/c/header.h"),
+    ),
+    (
+        "c/source.c",
+        include_str!("../resources/templates/c/source.c"),
+    ),
+    ("c/case.c", include_str!("../resources/templates/c/case.c")),
+    (
+        "c/guard.c",
+        include_str!("../resources/templates/c/guard.c"),
+    ),
+    (
+        "c/moore/case_code.c",
+        include_str!("../resources/templates/c/moore/case_code.c"),
+    ),
+    (
+        "c/moore/case_if.c",
+        include_str!("../resources/templates/c/moore/case_if.c"),
+    ),
+    (
+        "c/mealy/case_code.c",
+        include_str!("../resources/templates/c/mealy/case_code.c"),
+    ),
+    (
+        "c/mealy/case_if.c",
+        include_str!("../resources/templates/c/mealy/case_if.c"),
+    ),
+    (
+        "cpp/machine.hpp",
+        include_str!("../resources/templates/cpp/machine.hpp"),
+    ),
+    (
+        "go/machine.go",
+        include_str!("../resources/templates/go/machine.go"),
+    ),
+    (
+        "go/machine_test.go",
+        include_str!("../resources/templates/go/machine_test.go"),
+    ),
+    (
+        "typescript/machine.ts",
+        include_str!("../resources/templates/typescript/machine.ts"),
+    ),
+    (
+        "systemverilog/machine.sv",
+        include_str!("../resources/templates/systemverilog/machine.sv"),
+    ),
+    (
+        "vhdl/machine.vhd",
+        include_str!("../resources/templates/vhdl/machine.vhd"),
+    ),
+    (
+        "java/Machine.java",
+        include_str!("../resources/templates/java/Machine.java"),
+    ),
+    (
+        "kotlin/Machine.kt",
+        include_str!("../resources/templates/kotlin/Machine.kt"),
+    ),
+    (
+        "scxml/machine.scxml",
+        include_str!("../resources/templates/scxml/machine.scxml"),
+    ),
+    (
+        "kiss2/machine.kiss2",
+        include_str!("../resources/templates/kiss2/machine.kiss2"),
+    ),
+    (
+        "blif/machine.blif",
+        include_str!("../resources/templates/blif/machine.blif"),
+    ),
+    (
+        "rust/machine.rs",
+        include_str!("../resources/templates/rust/machine.rs"),
+    ),
+];
+
+/// The templates of one language.
+#[derive(Debug)]
+pub struct Templates {
+    env: Environment<'static>,
+}
+
+impl Templates {
+    /// Creates the templates of a language. If a directory is given, the templates are loaded
+    /// from it. Templates missing in the directory fall back to the built-in ones.
+    ///
+    /// # Arguments
+    ///
+    /// * `language` - The language of the templates, e.g. `c`.
+    /// * `dir` - The optional template directory.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Templates)` if all templates could be loaded and compiled.
+    /// * `Err(String)` if the directory does not exist or a template is invalid.
+    pub fn new(language: &str, dir: Option<&Path>) -> Result<Self, String> {
+        if let Some(dir) = dir {
+            if !dir.is_dir() {
+                return Err(format!(
+                    "The template directory {} does not exist.",
+                    dir.display()
+                ));
+            }
+        }
+
+        let mut env = Environment::new();
+        env.set_undefined_behavior(UndefinedBehavior::Strict);
+        env.set_trim_blocks(true);
+        env.set_lstrip_blocks(true);
+        env.set_keep_trailing_newline(true);
+        env.add_filter("snake_case", |s: &str| s.to_snake_case());
+        env.add_filter("upper_snake_case", |s: &str| s.to_shouty_snake_case());
+        env.add_filter("camel_case", |s: &str| s.to_lower_camel_case());
+        env.add_filter("pascal_case", |s: &str| s.to_upper_camel_case());
+        env.add_filter("ljust", |s: &str, width: usize| format!("{:<1$}", s, width));
+
+        let prefix = format!("{}/", language);
+        let loader_dir = dir.map(PathBuf::from);
+        env.set_loader(move |name| load(&prefix, loader_dir.as_deref(), name));
+
+        let templates = Templates { env };
+
+        // Compile every template up front, so that syntax errors in custom templates
+        // are reported before anything is generated.
+        for name in templates.names(language) {
+            templates
+                .env
+                .get_template(name)
+                .map_err(|e| format!("Invalid template {}: {:#}", name, e))?;
+            if dir.is_some_and(|dir| dir.join(name).is_file()) {
+                templates.check_custom(language, name)?;
+            }
+        }
+
+        Ok(templates)
+    }
+
+    /// Checks that a custom template uses all the data and includes all the templates
+    /// the built-in template does, so that it cannot silently drop a part of the machine,
+    /// e.g. the transitions of a state.
+    fn check_custom(&self, language: &str, name: &str) -> Result<(), String> {
+        let builtin_name = format!("{}/{}", language, name);
+        let builtin = BUILTIN_TEMPLATES
+            .iter()
+            .find(|(builtin, _)| *builtin == builtin_name)
+            .map(|(_, template)| *template)
+            .unwrap();
+        let builtin_env = Environment::new();
+        let builtin = builtin_env.template_from_str(builtin).unwrap();
+        let custom = self.env.get_template(name).unwrap();
+
+        let used = custom.undeclared_variables(true);
+        let is_used = |variable: &String| {
+            used.iter()
+                .any(|u| variable == u || variable.starts_with(&format!("{}.", u)))
+        };
+        let mut missing: Vec<String> = builtin
+            .undeclared_variables(true)
+            .into_iter()
+            .filter(|variable| !is_used(variable))
+            .collect();
+        missing.sort();
+
+        let included = includes(custom.source());
+        for include in includes(builtin.source()) {
+            if !included.contains(&include) {
+                missing.push(format!("{{% include {} %}}", include));
+            }
+        }
+
+        if !missing.is_empty() {
+            return Err(format!(
+                "The template {} does not use {}, which the built-in template uses.",
+                name,
+                missing.join(", ")
+            ));
+        }
+        Ok(())
+    }
+
+    /// Renders a template. Every variable used by the template must be defined in the context.
+    pub fn render<S: Serialize>(&self, name: &str, context: &S) -> Result<String, String> {
+        self.env
+            .get_template(name)
+            .and_then(|t| t.render(context))
+            .map_err(|e| format!("Could not render template {}: {:#}", name, e))
+    }
+
+    /// The names of the built-in templates of a language.
+    fn names<'a>(&self, language: &'a str) -> impl Iterator<Item = &'static str> + 'a {
+        BUILTIN_TEMPLATES
+            .iter()
+            .filter_map(move |(name, _)| name.strip_prefix(language)?.strip_prefix('/'))
+    }
+}
+
+/// Returns the expressions of the `include` tags of a template with single spaces.
+fn includes(source: &str) -> Vec<String> {
+    source
+        .split("{%")
+        .skip(1)
+        .filter_map(|tag| {
+            let tag = tag
+                .split("%}")
+                .next()?
+                .trim_matches(|c: char| c == '-' || c == '+');
+            let expression = tag.trim_start().strip_prefix("include")?;
+            Some(expression.split_whitespace().collect::<Vec<_>>().join(" "))
+        })
+        .collect()
+}
+
+/// Loads a template from the directory or falls back to the built-in template.
+fn load(prefix: &str, dir: Option<&Path>, name: &str) -> Result<Option<String>, minijinja::Error> {
+    if let Some(dir) = dir {
+        let path = dir.join(name);
+        if path.is_file() {
+            return std::fs::read_to_string(&path).map(Some).map_err(|e| {
+                minijinja::Error::new(
+                    minijinja::ErrorKind::InvalidOperation,
+                    format!("could not read {}", path.display()),
+                )
+                .with_source(e)
+            });
+        }
+    }
+
+    let name = format!("{}{}", prefix, name);
+    Ok(BUILTIN_TEMPLATES
+        .iter()
+        .find(|(builtin, _)| *builtin == name)
+        .map(|(_, template)| template.to_string()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_support::temp_dir;
+
+    /// Creates an empty, unique template directory in the temp directory.
+    fn create_template_dir(test_name: &str) -> PathBuf {
+        let dir = temp_dir(test_name);
+        std::fs::create_dir_all(dir.join("moore")).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_builtin_templates_compile() {
+        assert!(Templates::new("c", None).is_ok());
+    }
+
+    #[test]
+    fn test_templates_from_dir_fall_back_to_builtin() {
+        let dir = create_template_dir("fallback");
+        let header = include_str!("../resources/templates/c/header.h");
+        std::fs::write(dir.join("header.h"), format!("/* Copyright */\n{}", header)).unwrap();
+
+        let templates = Templates::new("c", Some(&dir)).unwrap();
+        let template = templates.env.get_template("header.h").unwrap();
+
+        assert!(template.source().starts_with("/* Copyright */\n"));
+        assert!(templates.env.get_template("source.c").is_ok());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_templates_from_dir_syntax_error() {
+        let dir = create_template_dir("syntax_error");
+        std::fs::write(dir.join("moore/case_if.c"), "{% if %}").unwrap();
+
+        let result = Templates::new("c", Some(&dir));
+
+        assert!(result.unwrap_err().contains("moore/case_if.c"));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_templates_from_dir_missing_data() {
+        let dir = create_template_dir("missing_data");
+        std::fs::write(
+            dir.join("moore/case_code.c"),
+            "{% for output in state.outputs %}\n        ctx->output.{{ output }} = true;\n{% endfor %}\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("case.c"),
+            "    case {{ name }}_{{ state.name }}:\n",
+        )
+        .unwrap();
+
+        let error = Templates::new("c", Some(&dir)).unwrap_err();
+
+        assert_eq!(
+            error,
+            "The template case.c does not use {% include kind ~ \"/case_code.c\" %}, which the built-in template uses."
+        );
+        std::fs::remove_file(dir.join("case.c")).unwrap();
+        let error = Templates::new("c", Some(&dir)).unwrap_err();
+        assert_eq!(
+            error,
+            "The template moore/case_code.c does not use state.transitions, {% include \"moore/case_if.c\" %}, which the built-in template uses."
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_includes() {
+        assert_eq!(
+            includes(
+                "a {% include kind ~ \"/case_code.c\" %} b {%- include \"guard.c\" -%} {% if x %}"
+            ),
+            ["kind ~ \"/case_code.c\"", "\"guard.c\""]
+        );
+    }
+
+    #[test]
+    fn test_templates_from_missing_dir() {
+        let dir = std::env::temp_dir().join("state_machine_builder_does_not_exist");
+
+        assert!(Templates::new("c", Some(&dir)).is_err());
+    }
+
+    #[test]
+    fn test_render_undefined_variable() {
+        let dir = create_template_dir("undefined_variable");
+        let header = include_str!("../resources/templates/c/header.h");
+        std::fs::write(dir.join("header.h"), format!("{}{{{{ unknown }}}}", header)).unwrap();
+
+        let templates = Templates::new("c", Some(&dir)).unwrap();
+        let context = minijinja::context! { name => "test" };
+
+        assert!(templates.render("header.h", &context).is_err());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_case_filters() {
+        let templates = Templates::new("c", None).unwrap();
+        let env = &templates.env;
+        let render = |expr: &str| {
+            env.render_str(expr, minijinja::context! { s => "motorControl" })
+                .unwrap()
+        };
+
+        assert_eq!(render("{{ s | snake_case }}"), "motor_control");
+        assert_eq!(render("{{ s | upper_snake_case }}"), "MOTOR_CONTROL");
+        assert_eq!(render("{{ s | pascal_case }}"), "MotorControl");
+        assert_eq!(render("{{ s | snake_case | camel_case }}"), "motorControl");
+        assert_eq!(render("{{ s | upper }}"), "MOTORCONTROL");
+        assert_eq!(render("{{ s | ljust(14) }}|"), "motorControl  |");
+    }
+}
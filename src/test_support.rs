@@ -0,0 +1,93 @@
+/*!
+Helpers shared by the unit tests.
+*/
+
+use std::ffi::OsStr;
+use std::path::PathBuf;
+use std::process::Command;
+
+use crate::files::Files;
+use crate::input::{self, Format};
+use crate::state_machines::StateMachine;
+
+/// Parses a YAML string into a Moore or Mealy machine like a machine file.
+pub fn parse(yaml_str: &str) -> StateMachine {
+    input::parse(yaml_str, Format::Yaml).unwrap()
+}
+
+/// Returns an empty, unique directory in the temp directory.
+pub fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "state_machine_builder_{}_{}",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Compares the generated files with the golden files in `resources/golden/{dir}`, so
+/// that the output of the backends is pinned without their toolchains. With the
+/// environment variable `UPDATE_GOLDEN` set, the golden files are written instead and
+/// the changes can be reviewed in the diff.
+pub fn assert_golden(dir: &str, files: &Files) {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("resources/golden")
+        .join(dir);
+    for (name, content) in files.contents() {
+        let path = dir.join(name);
+        if std::env::var_os("UPDATE_GOLDEN").is_some() {
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(&path, content).unwrap();
+        } else {
+            let golden = std::fs::read_to_string(&path)
+                .unwrap_or_else(|e| panic!("Could not read {}: {}", path.display(), e));
+            assert!(
+                golden == content,
+                "{} differs from the generated code, run the tests with UPDATE_GOLDEN=1 \
+                 to update it:\n{}",
+                path.display(),
+                content
+            );
+        }
+    }
+}
+
+/// Returns the tool from the environment variable (e.g. `CC`) or the default tool.
+fn tool(var: &str, default: &str) -> String {
+    std::env::var(var).unwrap_or_else(|_| default.to_string())
+}
+
+/// Returns whether the tool from the environment variable or the default tool is
+/// installed. Tests with tools that are not needed to build the crate are skipped
+/// without them.
+pub fn has_tool(var: &str, default: &str) -> bool {
+    let installed = Command::new(tool(var, default))
+        .arg("--version")
+        .output()
+        .is_ok();
+    if !installed {
+        eprintln!("Skipped: {} is not installed.", tool(var, default));
+    }
+    installed
+}
+
+/// Runs the tool from the environment variable (e.g. `CC`) or the default tool and
+/// panics with its error output if it fails. Returns its standard output.
+pub fn run_tool<S: AsRef<OsStr>>(var: &str, default: &str, args: &[S]) -> String {
+    let tool = tool(var, default);
+    let output = Command::new(&tool)
+        .args(args)
+        .output()
+        .unwrap_or_else(|e| panic!("Could not run {}: {}", tool, e));
+
+    assert!(
+        output.status.success(),
+        "{} failed:\n{}{}",
+        tool,
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
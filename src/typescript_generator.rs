@@ -0,0 +1,135 @@
+/*!
+TypeScript code generator
+
+This module generates a TypeScript module from a state machine. The states are a
+union of string literals, the inputs and outputs are interfaces, and the state machine
+is a pure `step(state, input)` function.
+
+The generated code is written to a file or directory.
+*/
+
+use crate::files::Files;
+use crate::model::Model;
+use crate::state_machines::StateMachine;
+use crate::templates::Templates;
+
+/// The language of the templates in `resources/templates`.
+pub const LANGUAGE: &str = "typescript";
+
+/// Generates the TypeScript module for the state machine. The code is written to a file or directory.
+/// The name is used for the file name.
+pub fn generate(
+    name: &str,
+    state_machine: &StateMachine,
+    templates: &Templates,
+    mut files: Files,
+) -> Result<Files, String> {
+    let model = Model::new(name, state_machine);
+
+    files.add_file(
+        format!("{}.ts", name),
+        templates.render("machine.ts", &model)?,
+    );
+
+    Ok(files)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_support::{assert_golden, has_tool, parse, run_tool, temp_dir};
+    use std::path::Path;
+
+    fn render(yaml_str: &str) -> String {
+        let templates = Templates::new(LANGUAGE, None).unwrap();
+        templates
+            .render("machine.ts", &Model::new("test", &parse(yaml_str)))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_moore_module() {
+        let code = render(include_str!("../resources/test_moore.yaml"));
+
+        assert!(code.contains("export type State =\n  | \"q1\"\n  | \"q2\"\n  | \"q3\";\n"));
+        assert!(code.contains("export const initialState: State = \"q1\";"));
+        assert!(code.contains("export interface Input {\n  i0: boolean;\n  i1: boolean;\n}"));
+        assert!(code.contains("const output: Output = { o5: false, o6: false };"));
+        assert!(code.contains(
+            "    case \"q1\":\n      output.o5 = true;\n      output.o6 = true;\n      if (input.i0 && input.i1) {\n        return { state: \"q2\", output };\n      }\n"
+        ));
+        assert!(code.contains("  return state === \"q3\";\n"));
+    }
+
+    #[test]
+    fn test_mealy_module() {
+        let code = render(include_str!("../resources/test_mealy.yaml"));
+
+        assert!(code.contains(
+            "      if (!input.i0) {\n        output.o0 = true;\n        return { state: \"q1\", output };\n"
+        ));
+    }
+
+    #[test]
+    fn test_minimal_module() {
+        let code = render(
+            "
+states: [idle]
+input_alphabet: []
+transitions: []
+output_function: []
+start_state: idle
+end_states: []
+output_alphabet: []
+",
+        );
+
+        assert!(code.contains("export type State =\n  | \"idle\";\n"));
+        assert!(code.contains("const output: Output = {};"));
+        assert!(code.contains("  return false;\n"));
+    }
+
+    /// Pins the generated modules, so that changes show up without the TypeScript compiler.
+    #[test]
+    fn test_golden() {
+        let templates = Templates::new(LANGUAGE, None).unwrap();
+        for (name, yaml_str) in [
+            ("moore", include_str!("../resources/test_moore.yaml")),
+            ("mealy", include_str!("../resources/test_mealy.yaml")),
+        ] {
+            let files = generate(name, &parse(yaml_str), &templates, Files::new("".into()));
+            assert_golden(LANGUAGE, &files.unwrap());
+        }
+    }
+
+    /// Checks that the generated modules compile in strict mode.
+    #[test]
+    fn test_typescript_compiles() {
+        if !has_tool("TSC", "tsc") {
+            return;
+        }
+        for yaml_str in [
+            include_str!("../resources/test_moore.yaml"),
+            include_str!("../resources/test_mealy.yaml"),
+        ] {
+            let dir = temp_dir("typescript_compiles");
+            let templates = Templates::new(LANGUAGE, None).unwrap();
+            let files = generate(
+                "machine",
+                &parse(yaml_str),
+                &templates,
+                Files::new(dir.clone()),
+            );
+            files.unwrap().write().unwrap();
+
+            let args = [
+                Path::new("--strict"),
+                Path::new("--noEmit"),
+                &dir.join("machine.ts"),
+            ];
+            run_tool("TSC", "tsc", &args);
+
+            std::fs::remove_dir_all(dir).unwrap();
+        }
+    }
+}
@@ -0,0 +1,251 @@
+/*!
+SystemVerilog code generator
+
+This module generates a synthesizable SystemVerilog module from a state machine.
+The module has a state register, combinational next state logic, and combinational
+output logic. The outputs of a Moore machine depend on the state only, the outputs of
+a Mealy machine on the state and the inputs.
+
+The generated code is written to a file or directory.
+*/
+
+use std::collections::HashMap;
+
+use minijinja::{context, Value};
+
+use crate::files::Files;
+use crate::model::Model;
+use crate::state_machines::StateMachine;
+use crate::templates::Templates;
+
+/// The language of the templates in `resources/templates`.
+pub const LANGUAGE: &str = "systemverilog";
+
+/// The encoding of the states in the state register.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    /// The index of the state.
+    Binary,
+    /// One bit per state.
+    OneHot,
+    /// The Gray code of the index, so that consecutive states differ in one bit.
+    Gray,
+}
+
+impl Encoding {
+    /// The name used in the generated code.
+    pub fn name(self) -> &'static str {
+        match self {
+            Encoding::Binary => "binary",
+            Encoding::OneHot => "one-hot",
+            Encoding::Gray => "gray",
+        }
+    }
+
+    /// The number of bits of the state register for the number of states.
+    pub fn width(self, states: usize) -> usize {
+        match self {
+            Encoding::OneHot => states.max(1),
+            Encoding::Binary | Encoding::Gray => {
+                let mut width = 1;
+                while (1usize << width) < states {
+                    width += 1;
+                }
+                width
+            }
+        }
+    }
+
+    /// The code of the state with the index as bits, most significant bit first, e.g. `01`.
+    pub fn bits(self, index: usize, width: usize) -> String {
+        match self {
+            Encoding::Binary => format!("{:0width$b}", index, width = width),
+            Encoding::Gray => format!("{:0width$b}", index ^ (index >> 1), width = width),
+            Encoding::OneHot => (0..width)
+                .rev()
+                .map(|bit| if bit == index { '1' } else { '0' })
+                .collect(),
+        }
+    }
+
+    /// The code of the state with the index as SystemVerilog literal, e.g. `2'b01`.
+    fn code(self, index: usize, width: usize) -> String {
+        format!("{}'b{}", width, self.bits(index, width))
+    }
+}
+
+/// Generates the SystemVerilog module for the state machine. The code is written to a file or directory.
+/// The name is used for the file and module name.
+pub fn generate(
+    name: &str,
+    state_machine: &StateMachine,
+    templates: &Templates,
+    encoding: Encoding,
+    mut files: Files,
+) -> Result<Files, String> {
+    let model = Model::new(name, state_machine);
+
+    files.add_file(
+        format!("{}.sv", name),
+        templates.render("machine.sv", &create_context(&model, encoding))?,
+    );
+
+    Ok(files)
+}
+
+/// Creates the template context: the model with the encoding and the code of every state.
+fn create_context(model: &Model, encoding: Encoding) -> Value {
+    let width = encoding.width(model.states.len());
+    let codes = model
+        .states
+        .iter()
+        .map(|s| (s.name.clone(), encoding.code(s.index, width)))
+        .collect::<HashMap<String, String>>();
+
+    context! {
+        encoding => encoding.name(),
+        state_width => width,
+        state_codes => codes,
+        ..Value::from_serialize(model)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_support::{assert_golden, has_tool, parse, run_tool, temp_dir};
+    use std::path::Path;
+
+    fn render(yaml_str: &str, encoding: Encoding) -> String {
+        let model = Model::new("fsm", &parse(yaml_str));
+        let templates = Templates::new(LANGUAGE, None).unwrap();
+        templates
+            .render("machine.sv", &create_context(&model, encoding))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_encoding_width() {
+        assert_eq!(Encoding::Binary.width(1), 1);
+        assert_eq!(Encoding::Binary.width(2), 1);
+        assert_eq!(Encoding::Binary.width(3), 2);
+        assert_eq!(Encoding::Binary.width(5), 3);
+        assert_eq!(Encoding::Gray.width(4), 2);
+        assert_eq!(Encoding::OneHot.width(5), 5);
+    }
+
+    #[test]
+    fn test_encoding_code() {
+        assert_eq!(Encoding::Binary.code(3, 3), "3'b011");
+        assert_eq!(Encoding::Gray.code(2, 2), "2'b11");
+        assert_eq!(Encoding::Gray.code(3, 2), "2'b10");
+        assert_eq!(Encoding::OneHot.code(0, 3), "3'b001");
+        assert_eq!(Encoding::OneHot.code(2, 3), "3'b100");
+    }
+
+    #[test]
+    fn test_moore_module() {
+        let code = render(
+            include_str!("../resources/test_moore.yaml"),
+            Encoding::Binary,
+        );
+
+        assert!(code.contains(
+            "module fsm (\n    input  logic clk,\n    input  logic rst,\n    input  logic i0,\n"
+        ));
+        assert!(code.contains("    output logic o6,\n    output logic is_end\n);"));
+        assert!(code.contains("typedef enum logic [1:0] {\n        S_q1 = 2'b00,\n        S_q2 = 2'b01,\n        S_q3 = 2'b10\n    } state_t;"));
+        assert!(code.contains(
+            "    always_ff @(posedge clk) begin\n        if (rst)\n            state <= S_q1;"
+        ));
+        assert!(code
+            .contains("                if (i0 && i1)\n                    next_state = S_q2;\n"));
+        assert!(code.contains("            S_q1: begin\n                o5 = 1'b1;\n                o6 = 1'b1;\n            end\n"));
+        assert!(code.contains("assign is_end = (state == S_q3);"));
+    }
+
+    #[test]
+    fn test_mealy_module() {
+        let code = render(
+            include_str!("../resources/test_mealy.yaml"),
+            Encoding::OneHot,
+        );
+
+        assert!(code.contains("typedef enum logic [2:0] {\n        S_q1 = 3'b001,"));
+        assert!(code
+            .contains("                else if (!i0)\n                    next_state = S_q1;\n"));
+        assert!(code.contains(
+            "                if (i0 && !i1) begin\n                    o0 = 1'b1;\n                end\n"
+        ));
+    }
+
+    /// Pins the generated modules with every encoding, so that changes show up without
+    /// Verilator.
+    #[test]
+    fn test_golden() {
+        let templates = Templates::new(LANGUAGE, None).unwrap();
+        for (name, yaml_str, encoding) in [
+            (
+                "moore",
+                include_str!("../resources/test_moore.yaml"),
+                Encoding::Binary,
+            ),
+            (
+                "moore_gray",
+                include_str!("../resources/test_moore.yaml"),
+                Encoding::Gray,
+            ),
+            (
+                "mealy",
+                include_str!("../resources/test_mealy.yaml"),
+                Encoding::OneHot,
+            ),
+        ] {
+            let files = generate(
+                name,
+                &parse(yaml_str),
+                &templates,
+                encoding,
+                Files::new("".into()),
+            );
+            assert_golden(LANGUAGE, &files.unwrap());
+        }
+    }
+
+    /// Checks that the generated modules pass the lint of Verilator with both encodings.
+    #[test]
+    fn test_verilator_lint() {
+        if !has_tool("VERILATOR", "verilator") {
+            return;
+        }
+        for (yaml_str, encoding) in [
+            (
+                include_str!("../resources/test_moore.yaml"),
+                Encoding::Binary,
+            ),
+            (
+                include_str!("../resources/test_mealy.yaml"),
+                Encoding::OneHot,
+            ),
+        ] {
+            let dir = temp_dir("verilator_lint");
+            let templates = Templates::new(LANGUAGE, None).unwrap();
+            let files = generate(
+                "fsm",
+                &parse(yaml_str),
+                &templates,
+                encoding,
+                Files::new(dir.clone()),
+            );
+            files.unwrap().write().unwrap();
+
+            run_tool(
+                "VERILATOR",
+                "verilator",
+                &[Path::new("--lint-only"), &dir.join("fsm.sv")],
+            );
+
+            std::fs::remove_dir_all(dir).unwrap();
+        }
+    }
+}
@@ -0,0 +1,143 @@
+/*!
+VHDL code generator
+
+This module generates a VHDL entity and architecture from a state machine. The
+states are an enumerated type, the state register is a clocked process with a
+synchronous reset to the start state, and the outputs are combinational logic.
+
+The generated code is written to a file or directory.
+*/
+
+use crate::files::Files;
+use crate::model::Model;
+use crate::state_machines::StateMachine;
+use crate::templates::Templates;
+
+/// The language of the templates in `resources/templates`.
+pub const LANGUAGE: &str = "vhdl";
+
+/// Generates the VHDL entity and architecture for the state machine. The code is written to a file or directory.
+/// The name is used for the file and entity name.
+pub fn generate(
+    name: &str,
+    state_machine: &StateMachine,
+    templates: &Templates,
+    mut files: Files,
+) -> Result<Files, String> {
+    let model = Model::new(name, state_machine);
+
+    files.add_file(
+        format!("{}.vhd", name),
+        templates.render("machine.vhd", &model)?,
+    );
+
+    Ok(files)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_support::{assert_golden, has_tool, parse, run_tool, temp_dir};
+
+    fn render(yaml_str: &str) -> String {
+        let templates = Templates::new(LANGUAGE, None).unwrap();
+        templates
+            .render("machine.vhd", &Model::new("fsm", &parse(yaml_str)))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_moore_entity() {
+        let code = render(include_str!("../resources/test_moore.yaml"));
+
+        assert!(code.contains("entity fsm is\n    port (\n        clk : in std_logic;\n        rst : in std_logic;\n        i0 : in std_logic;\n"));
+        assert!(code.contains(
+            "        o6 : out std_logic;\n        is_end : out std_logic\n    );\nend entity fsm;"
+        ));
+        assert!(code.contains("    type state_t is (S_q1, S_q2, S_q3);"));
+        assert!(code.contains("            if rst = '1' then\n                state <= S_q1;"));
+        assert!(code.contains("    next_state_logic : process (state, i0, i1)"));
+        assert!(code.contains(
+            "            when S_q1 =>\n                if i0 = '1' and i1 = '1' then\n                    next_state <= S_q2;\n                end if;\n"
+        ));
+        assert!(code.contains("            when S_q3 =>\n                null;\n"));
+        assert!(code.contains("    output_logic : process (state)"));
+        assert!(code.contains(
+            "            when S_q1 =>\n                o5 <= '1';\n                o6 <= '1';\n"
+        ));
+        assert!(code.contains("    is_end <= '1' when state = S_q3 else '0';"));
+    }
+
+    #[test]
+    fn test_mealy_entity() {
+        let code = render(include_str!("../resources/test_mealy.yaml"));
+
+        assert!(code.contains("    output_logic : process (state, i0, i1)"));
+        assert!(code.contains(
+            "                if i1 = '1' then\n                    o1 <= '1';\n                elsif i0 = '0' then\n                    o0 <= '1';\n                end if;\n"
+        ));
+    }
+
+    #[test]
+    fn test_without_end_states() {
+        let code = render(
+            "
+states: [idle]
+input_alphabet: []
+transitions: []
+output_function: []
+start_state: idle
+end_states: []
+output_alphabet: []
+",
+        );
+
+        assert!(code.contains("    next_state_logic : process (state)"));
+        assert!(!code.contains("output_logic"));
+        assert!(code.contains("    is_end <= '0';"));
+    }
+
+    /// Pins the generated entities, so that changes show up without GHDL.
+    #[test]
+    fn test_golden() {
+        let templates = Templates::new(LANGUAGE, None).unwrap();
+        for (name, yaml_str) in [
+            ("moore", include_str!("../resources/test_moore.yaml")),
+            ("mealy", include_str!("../resources/test_mealy.yaml")),
+        ] {
+            let files = generate(name, &parse(yaml_str), &templates, Files::new("".into()));
+            assert_golden(LANGUAGE, &files.unwrap());
+        }
+    }
+
+    /// Checks that the generated entities are analyzed by GHDL.
+    #[test]
+    fn test_ghdl_analyze() {
+        if !has_tool("GHDL", "ghdl") {
+            return;
+        }
+        for yaml_str in [
+            include_str!("../resources/test_moore.yaml"),
+            include_str!("../resources/test_mealy.yaml"),
+        ] {
+            let dir = temp_dir("ghdl_analyze");
+            let templates = Templates::new(LANGUAGE, None).unwrap();
+            let files = generate("fsm", &parse(yaml_str), &templates, Files::new(dir.clone()));
+            files.unwrap().write().unwrap();
+
+            let workdir = format!("--workdir={}", dir.display());
+            run_tool(
+                "GHDL",
+                "ghdl",
+                &[
+                    "-a",
+                    "--std=08",
+                    &workdir,
+                    &dir.join("fsm.vhd").display().to_string(),
+                ],
+            );
+
+            std::fs::remove_dir_all(dir).unwrap();
+        }
+    }
+}
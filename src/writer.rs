@@ -0,0 +1,226 @@
+/*!
+Canonical YAML writer
+
+This module writes a state machine as YAML in a canonical layout, so that machines
+converted from other formats or changed by tools have a stable form and small diffs:
+
+- The fields are written in a fixed order, starting with `type` and `version`.
+- All strings are double-quoted.
+- Lists are indented by two spaces. The lists inside transitions and outputs are
+  written on one line, e.g. `read_symbol: ["i0", "!i1"]`.
+
+The order of the states, symbols and transitions is kept, since it is significant.
+Comments of the original file are not kept. JSON and TOML are written with the same
+fields, in the order of the serializer.
+*/
+
+use serde_json::Value;
+
+use crate::input::VERSION;
+use crate::state_machines::StateMachine;
+
+/// The fields of a machine in the order they are written. Fields of `Machine` that are
+/// missing here are not written.
+const FIELDS: [&str; 8] = [
+    "states",
+    "input_alphabet",
+    "output_alphabet",
+    "start_state",
+    "end_states",
+    "transitions",
+    "output_function",
+    "variables",
+];
+
+/// The fields of the list entries (transitions, outputs, variables) in the order they are written.
+const ENTRY_FIELDS: [&str; 7] = [
+    "name",
+    "type",
+    "initial",
+    "current_state",
+    "read_symbol",
+    "new_state",
+    "output_symbol",
+];
+
+/// Returns the machine as YAML in the canonical layout.
+pub fn to_yaml(state_machine: &StateMachine) -> String {
+    let machine_type = match state_machine {
+        StateMachine::Moore(_) => "moore",
+        StateMachine::Mealy(_) => "mealy",
+    };
+    let machine = serde_json::to_value(state_machine).unwrap();
+
+    let mut yaml = format!("type: {}\nversion: {}\n", machine_type, VERSION);
+    for field in FIELDS {
+        match machine.get(field) {
+            None | Some(Value::Null) => {}
+            Some(Value::Array(elements)) if !elements.is_empty() => {
+                yaml += &format!("{}:\n", field);
+                for element in elements {
+                    write_element(&mut yaml, element);
+                }
+            }
+            Some(value) => yaml += &format!("{}: {}\n", field, flow(value)),
+        }
+    }
+    yaml
+}
+
+/// Writes an element of a top-level list: a scalar or an entry with one field per line.
+fn write_element(yaml: &mut String, element: &Value) {
+    let Value::Object(entry) = element else {
+        *yaml += &format!("  - {}\n", flow(element));
+        return;
+    };
+
+    let mut prefix = "  - ";
+    for field in ENTRY_FIELDS {
+        if let Some(value) = entry.get(field) {
+            *yaml += &format!("{}{}: {}\n", prefix, field, flow(value));
+            prefix = "    ";
+        }
+    }
+}
+
+/// Returns the machine as JSON with its `type` and `version`.
+pub fn to_json(state_machine: &StateMachine) -> String {
+    serde_json::to_string_pretty(&to_value(state_machine)).unwrap() + "\n"
+}
+
+/// Returns the machine as TOML with its `type` and `version`.
+pub fn to_toml(state_machine: &StateMachine) -> String {
+    toml::to_string(&to_value(state_machine)).unwrap()
+}
+
+/// Returns the machine with its `type` and `version` and without empty optional fields.
+fn to_value(state_machine: &StateMachine) -> Value {
+    let machine_type = match state_machine {
+        StateMachine::Moore(_) => "moore",
+        StateMachine::Mealy(_) => "mealy",
+    };
+    let mut machine = serde_json::to_value(state_machine).unwrap();
+    let fields = machine.as_object_mut().unwrap();
+    fields.retain(|_, value| !value.is_null());
+    fields.insert("type".to_string(), machine_type.into());
+    fields.insert("version".to_string(), VERSION.into());
+    machine
+}
+
+/// Returns a value on one line. Strings are double-quoted.
+fn flow(value: &Value) -> String {
+    match value {
+        Value::Array(elements) => format!(
+            "[{}]",
+            elements.iter().map(flow).collect::<Vec<_>>().join(", ")
+        ),
+        // JSON strings are valid double-quoted YAML scalars.
+        _ => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::input::{self, Format};
+    use crate::test_support::parse;
+
+    #[test]
+    fn test_to_yaml_moore() {
+        let yaml = to_yaml(&parse(include_str!("../resources/test_moore.yaml")));
+
+        assert_eq!(
+            yaml,
+            r#"type: moore
+version: 1
+states:
+  - "q1"
+  - "q2"
+  - "q3"
+input_alphabet:
+  - "i0"
+  - "i1"
+output_alphabet:
+  - "o5"
+  - "o6"
+start_state: "q1"
+end_states:
+  - "q3"
+transitions:
+  - current_state: "q1"
+    read_symbol: ["i0", "i1"]
+    new_state: "q2"
+  - current_state: "q2"
+    read_symbol: ["i1"]
+    new_state: "q3"
+output_function:
+  - current_state: "q1"
+    output_symbol: ["o5", "o6"]
+"#
+        );
+    }
+
+    #[test]
+    fn test_to_yaml_mealy_with_variables() {
+        let yaml_str = format!(
+            "{}variables:\n  - name: counter\n    type: uint\n  - {{ initial: 2.5, type: float, name: gain }}\n",
+            include_str!("../resources/test_mealy.yaml")
+        );
+        let yaml = to_yaml(&parse(&yaml_str));
+
+        assert!(yaml.starts_with("type: mealy\n"));
+        assert!(!yaml.contains("output_function"));
+        assert!(yaml.contains("    new_state: \"q2\"\n    output_symbol: \"o0\"\n"));
+        assert!(yaml.ends_with(
+            "variables:\n  - name: \"counter\"\n    type: \"uint\"\n  - name: \"gain\"\n    type: \"float\"\n    initial: 2.5\n"
+        ));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for (contents, format) in [
+            (include_str!("../resources/test_moore.yaml"), Format::Yaml),
+            (include_str!("../resources/test_mealy.yaml"), Format::Yaml),
+            (include_str!("../resources/test_moore.json"), Format::Json),
+            (include_str!("../resources/test_mealy.toml"), Format::Toml),
+            (
+                include_str!("../resources/test_moore.puml"),
+                Format::PlantUml,
+            ),
+        ] {
+            let yaml = to_yaml(&input::parse(contents, format).unwrap());
+            let again = to_yaml(&input::parse(&yaml, Format::Yaml).unwrap());
+            assert_eq!(yaml, again);
+        }
+    }
+
+    #[test]
+    fn test_to_json_and_toml() {
+        for state_machine in [
+            parse(include_str!("../resources/test_moore.yaml")),
+            parse(include_str!("../resources/test_mealy.yaml")),
+        ] {
+            let yaml = to_yaml(&state_machine);
+            let json = to_json(&state_machine);
+            let toml = to_toml(&state_machine);
+
+            assert!(json.contains("\"version\": 1"));
+            assert!(!json.contains("null"));
+            assert_eq!(to_yaml(&input::parse(&json, Format::Json).unwrap()), yaml);
+            assert_eq!(to_yaml(&input::parse(&toml, Format::Toml).unwrap()), yaml);
+        }
+    }
+
+    #[test]
+    fn test_to_yaml_quotes_strings() {
+        let yaml_str =
+            include_str!("../resources/test_moore.yaml").replace("\"i1\"", "\"yes: no\"");
+        let yaml = to_yaml(&parse(&yaml_str));
+
+        assert!(yaml.contains("  - \"yes: no\"\n"));
+        assert!(matches!(
+            input::parse(&yaml, Format::Yaml).unwrap(),
+            StateMachine::Moore(_)
+        ));
+    }
+}
@@ -0,0 +1,191 @@
+/*!
+The tokens of a `state_machine!` definition
+
+The definition is a list of `key: value` fields like a YAML file. A value is a name
+(`q1`, `!i0`), a literal (`"q1"`, `3`, `-1.5`, `true`), a list `[...]` or a map `{...}`.
+The definition is converted to JSON and read like a machine file.
+*/
+
+use proc_macro2::Span;
+use serde_json::Value as Json;
+use syn::ext::IdentExt;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{braced, bracketed, Ident, Lit, Token};
+
+/// A `state_machine!` definition: the name of the module and the fields of the machine.
+pub struct Definition {
+    name: Ident,
+    fields: Vec<Field>,
+}
+
+/// A `key: value` field.
+#[derive(Clone)]
+struct Field {
+    key: Ident,
+    value: Value,
+}
+
+/// The value of a field or of a list element.
+#[derive(Clone)]
+enum Value {
+    Scalar(Json, Span),
+    List(Vec<Value>),
+    Map(Vec<Field>),
+}
+
+impl Definition {
+    /// Returns the name of the generated module.
+    pub fn name(&self) -> String {
+        self.name.to_string()
+    }
+
+    /// Returns the names in the values of the fields with one of the keys, with their
+    /// spans, in the order they are written. The fields of maps are searched as well,
+    /// e.g. `current_state` in the transitions. Negated names are returned without `!`.
+    pub fn names(&self, keys: &[&str]) -> Vec<(String, Span)> {
+        let mut names = Vec::new();
+        collect_names(&self.fields, keys, &mut names);
+        names
+    }
+
+    /// Returns the machine as JSON.
+    pub fn to_json(&self) -> Json {
+        Value::Map(self.fields.clone()).to_json()
+    }
+}
+
+/// Adds the names in the values of the fields with one of the keys.
+fn collect_names(fields: &[Field], keys: &[&str], names: &mut Vec<(String, Span)>) {
+    for field in fields {
+        if keys.iter().any(|key| field.key == key) {
+            field.value.collect_names(names);
+        }
+        field
+            .value
+            .for_each_map(&mut |fields| collect_names(fields, keys, names));
+    }
+}
+
+impl Value {
+    /// Adds the names of a scalar or of the scalars of a list.
+    fn collect_names(&self, names: &mut Vec<(String, Span)>) {
+        match self {
+            Value::Scalar(Json::String(name), span) => {
+                let name = name.strip_prefix('!').unwrap_or(name);
+                names.push((name.to_string(), *span));
+            }
+            Value::List(elements) => elements.iter().for_each(|e| e.collect_names(names)),
+            Value::Scalar(..) | Value::Map(_) => {}
+        }
+    }
+
+    /// Calls `f` with the fields of the maps in the value.
+    fn for_each_map(&self, f: &mut impl FnMut(&[Field])) {
+        match self {
+            Value::Map(fields) => f(fields),
+            Value::List(elements) => elements.iter().for_each(|e| e.for_each_map(f)),
+            Value::Scalar(..) => {}
+        }
+    }
+
+    fn to_json(&self) -> Json {
+        match self {
+            Value::Scalar(json, _) => json.clone(),
+            Value::List(elements) => Json::Array(elements.iter().map(Value::to_json).collect()),
+            Value::Map(fields) => Json::Object(
+                fields
+                    .iter()
+                    .map(|field| (field.key.unraw().to_string(), field.value.to_json()))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+impl Parse for Definition {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut fields: Vec<Field> = Punctuated::<Field, Token![,]>::parse_terminated(input)?
+            .into_iter()
+            .collect();
+
+        let index = fields
+            .iter()
+            .position(|field| field.key == "name")
+            .ok_or_else(|| input.error("expected a `name` field with the name of the module"))?;
+        let field = fields.remove(index);
+        let name = match field.value {
+            Value::Scalar(Json::String(name), span) if syn::parse_str::<Ident>(&name).is_ok() => {
+                Ident::new(&name, span)
+            }
+            _ => {
+                return Err(syn::Error::new(
+                    field.key.span(),
+                    "the name of the module must be an identifier",
+                ))
+            }
+        };
+
+        Ok(Definition { name, fields })
+    }
+}
+
+impl Parse for Field {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let key = Ident::parse_any(input)?;
+        input.parse::<Token![:]>()?;
+        let value = input.parse()?;
+        Ok(Field { key, value })
+    }
+}
+
+impl Parse for Value {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.peek(syn::token::Bracket) {
+            let content;
+            bracketed!(content in input);
+            let elements = Punctuated::<Value, Token![,]>::parse_terminated(&content)?;
+            return Ok(Value::List(elements.into_iter().collect()));
+        }
+        if input.peek(syn::token::Brace) {
+            let content;
+            braced!(content in input);
+            let fields = Punctuated::<Field, Token![,]>::parse_terminated(&content)?;
+            return Ok(Value::Map(fields.into_iter().collect()));
+        }
+        if input.peek(Token![!]) {
+            input.parse::<Token![!]>()?;
+            let symbol = Ident::parse_any(input)?;
+            return Ok(Value::Scalar(
+                Json::String(format!("!{}", symbol.unraw())),
+                symbol.span(),
+            ));
+        }
+        let negative = input.parse::<Option<Token![-]>>()?.is_some();
+        if input.peek(Lit) {
+            let literal: Lit = input.parse()?;
+            let json = match (&literal, negative) {
+                (Lit::Int(int), _) => {
+                    let value = int.base10_parse::<i64>()?;
+                    Json::from(if negative { -value } else { value })
+                }
+                (Lit::Float(float), _) => {
+                    let value = float.base10_parse::<f64>()?;
+                    Json::from(if negative { -value } else { value })
+                }
+                (Lit::Str(string), false) => Json::String(string.value()),
+                (Lit::Bool(boolean), false) => Json::Bool(boolean.value),
+                _ => return Err(syn::Error::new(literal.span(), "unsupported literal")),
+            };
+            return Ok(Value::Scalar(json, literal.span()));
+        }
+        if negative {
+            return Err(input.error("expected a number"));
+        }
+        let name = Ident::parse_any(input)?;
+        Ok(Value::Scalar(
+            Json::String(name.unraw().to_string()),
+            name.span(),
+        ))
+    }
+}
@@ -0,0 +1,340 @@
+/*!
+Procedural macro for State Machine Builder
+
+`state_machine!` defines a Moore or Mealy machine inline in Rust. It takes the same
+structure as the YAML files, plus the `name` of the generated module, and expands at
+compile time into the code of the Rust backend. The checks of `state_machine_builder`
+run during the expansion, and their errors are reported at the offending element.
+
+```
+use state_machine_macros::state_machine;
+
+state_machine! {
+    name: motor_sm,
+    states: [idle, running],
+    input_alphabet: [start],
+    output_alphabet: [motor_on],
+    transitions: [
+        { current_state: idle, read_symbol: [start], new_state: running },
+        { current_state: running, read_symbol: [!start], new_state: idle },
+    ],
+    output_function: [
+        { current_state: running, output_symbol: [motor_on] },
+    ],
+    start_state: idle,
+    end_states: [],
+}
+
+let mut machine = motor_sm::Machine::new();
+machine.step(motor_sm::Input { start: true });
+assert!(machine.step(motor_sm::Input { start: true }).motor_on);
+```
+
+A duplicate state is reported at its second declaration:
+
+```compile_fail
+use state_machine_macros::state_machine;
+
+state_machine! {
+    name: broken,
+    states: [idle, idle],
+    input_alphabet: [],
+    output_alphabet: [],
+    transitions: [],
+    output_function: [],
+    start_state: idle,
+    end_states: [],
+}
+```
+
+A transition to an undeclared state is reported at the state:
+
+```compile_fail
+use state_machine_macros::state_machine;
+
+state_machine! {
+    name: broken,
+    states: [idle],
+    input_alphabet: [],
+    output_alphabet: [],
+    transitions: [{ current_state: idle, read_symbol: [], new_state: running }],
+    output_function: [],
+    start_state: idle,
+    end_states: [],
+}
+```
+*/
+
+mod definition;
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use syn::parse_macro_input;
+
+use definition::Definition;
+use state_machine_builder::checks::{self, Finding, Severity};
+use state_machine_builder::templates::Templates;
+use state_machine_builder::{input, rust_generator, StateMachine};
+
+/// Defines a state machine and expands into a module with its `State`, `Input`,
+/// `Output` and `Machine` types. See the crate documentation for the syntax.
+#[proc_macro]
+pub fn state_machine(tokens: TokenStream) -> TokenStream {
+    let definition = parse_macro_input!(tokens as Definition);
+    match expand(&definition) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+/// Reads and checks the machine and returns the generated module.
+fn expand(definition: &Definition) -> Result<proc_macro2::TokenStream, syn::Error> {
+    let state_machine = check(definition)?;
+
+    let templates = Templates::new(rust_generator::LANGUAGE, None).map_err(call_site_error)?;
+    let source = rust_generator::render(&definition.name(), &state_machine, &templates)
+        .map_err(call_site_error)?;
+
+    source
+        .parse()
+        .map_err(|e| call_site_error(format!("{}", e)))
+}
+
+/// Reads the machine and runs all checks. Every error of the checks is reported at the
+/// element that causes it, other errors at the whole macro.
+fn check(definition: &Definition) -> Result<StateMachine, syn::Error> {
+    let json = definition.to_json().to_string();
+    let state_machine = input::parse(&json, input::Format::Json).map_err(call_site_error)?;
+
+    let errors = checks::find_all(&state_machine)
+        .into_iter()
+        .filter(|finding| finding.severity == Severity::Error)
+        .map(|finding| {
+            let span = locate(definition, &finding)
+                .map(|index| definition.names(keys(finding.rule))[index].1)
+                .unwrap_or_else(Span::call_site);
+            syn::Error::new(span, finding.message)
+        })
+        .reduce(|mut errors, error| {
+            errors.combine(error);
+            errors
+        });
+
+    match errors {
+        Some(errors) => Err(errors),
+        None => Ok(state_machine),
+    }
+}
+
+/// Returns the keys of the fields that the findings of a rule are about.
+fn keys(rule: &str) -> &'static [&'static str] {
+    match rule {
+        "unique-elements" | "name-collisions" | "legal-names" => {
+            &["states", "input_alphabet", "output_alphabet", "name"]
+        }
+        "variable-values" => &["name"],
+        "end-states" => &["end_states"],
+        "unknown-state" => &["start_state", "current_state", "new_state"],
+        "unknown-symbol" => &["read_symbol", "output_symbol"],
+        _ => &["states"],
+    }
+}
+
+/// Returns the index of the element of a finding in the names of the keys of its rule.
+/// A duplicate is located at its second declaration, other elements at their first
+/// occurrence.
+fn locate(definition: &Definition, finding: &Finding) -> Option<usize> {
+    let element = finding.element.as_deref()?;
+    let mut indices = definition
+        .names(keys(finding.rule))
+        .into_iter()
+        .enumerate()
+        .filter(|(_, (name, _))| name == element)
+        .map(|(index, _)| index);
+    match finding.rule {
+        "unique-elements" => indices.nth(1),
+        _ => indices.next(),
+    }
+}
+
+fn call_site_error(message: String) -> syn::Error {
+    syn::Error::new(Span::call_site(), message)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use quote::quote;
+
+    fn definition(tokens: proc_macro2::TokenStream) -> Definition {
+        syn::parse2(tokens).unwrap()
+    }
+
+    fn parse(definition: &Definition) -> StateMachine {
+        input::parse(&definition.to_json().to_string(), input::Format::Json).unwrap()
+    }
+
+    /// Returns the name at which the first error of the checks is reported.
+    fn located(definition: &Definition) -> String {
+        let findings = checks::find_all(&parse(definition));
+        let finding = findings
+            .iter()
+            .find(|f| f.severity == Severity::Error)
+            .unwrap();
+        let index = locate(definition, finding).unwrap();
+        definition.names(keys(finding.rule))[index].0.clone()
+    }
+
+    #[test]
+    fn test_expand_mealy() {
+        let definition = definition(quote! {
+            name: blinker,
+            type: mealy,
+            version: 1,
+            states: [off, on],
+            input_alphabet: [tick],
+            output_alphabet: [led],
+            transitions: [
+                { current_state: off, read_symbol: [tick], new_state: on, output_symbol: led },
+                { current_state: on, read_symbol: [!tick], new_state: off, output_symbol: "led" },
+            ],
+            start_state: off,
+            end_states: [],
+            variables: [{ name: count, type: int, initial: -2 }],
+        });
+
+        let source = expand(&definition).unwrap().to_string();
+        assert!(source.contains("pub mod blinker"));
+        assert!(source.contains("pub count : i32"));
+        assert!(matches!(check(&definition), Ok(StateMachine::Mealy(_))));
+    }
+
+    #[test]
+    fn test_locate_duplicate() {
+        let definition = definition(quote! {
+            name: test,
+            states: [q1, q2],
+            input_alphabet: [i0, q1],
+            output_alphabet: [],
+            transitions: [],
+            output_function: [],
+            start_state: q1,
+            end_states: [q2],
+        });
+
+        let error = check(&definition).unwrap_err();
+        assert_eq!(error.to_string(), "Duplicate elements found: q1");
+        let finding = &checks::find_all(&parse(&definition))[0];
+        assert_eq!(locate(&definition, finding), Some(3));
+        assert_eq!(definition.names(keys(finding.rule))[3].0, "q1");
+    }
+
+    #[test]
+    fn test_locate_end_state_and_name() {
+        let end_state = definition(quote! {
+            name: test,
+            end_states: [q1, q3],
+            states: [q1, q2],
+            input_alphabet: [],
+            output_alphabet: [],
+            transitions: [],
+            output_function: [],
+            start_state: q1,
+        });
+        let variable = definition(quote! {
+            name: test,
+            states: [q1],
+            input_alphabet: [],
+            output_alphabet: [],
+            transitions: [],
+            output_function: [],
+            start_state: q1,
+            end_states: [],
+            variables: [{ name: "1x", type: bool }],
+        });
+
+        assert_eq!(located(&end_state), "q3");
+        assert_eq!(located(&variable), "1x");
+    }
+
+    #[test]
+    fn test_locate_transition() {
+        let state = definition(quote! {
+            name: test,
+            states: [q1, q2],
+            input_alphabet: [i0],
+            output_alphabet: [],
+            transitions: [
+                { current_state: q1, read_symbol: [i0], new_state: q2 },
+                { current_state: q2, read_symbol: [], new_state: q3 },
+            ],
+            output_function: [],
+            start_state: q1,
+            end_states: [],
+        });
+        let symbol = definition(quote! {
+            name: test,
+            states: [q1, q2],
+            input_alphabet: [i0],
+            output_alphabet: [],
+            transitions: [
+                { current_state: q1, read_symbol: [i0, !i1], new_state: q2 },
+            ],
+            output_function: [],
+            start_state: q1,
+            end_states: [],
+        });
+
+        assert_eq!(
+            check(&state).unwrap_err().to_string(),
+            "The transition from q2 to q3 refers to q3, which is not a state."
+        );
+        assert_eq!(located(&state), "q3");
+        assert!(check(&symbol).unwrap_err().to_string().contains("reads i1"));
+        assert_eq!(located(&symbol), "i1");
+    }
+
+    #[test]
+    fn test_check_reports_all_errors() {
+        let definition = definition(quote! {
+            name: test,
+            states: [q1, q2],
+            input_alphabet: [i0],
+            output_alphabet: [],
+            transitions: [
+                { current_state: q1, read_symbol: [i0], new_state: q3 },
+                { current_state: q2, read_symbol: [i9], new_state: q1 },
+            ],
+            output_function: [],
+            start_state: q1,
+            end_states: [],
+        });
+
+        let messages: Vec<String> = check(&definition)
+            .unwrap_err()
+            .into_iter()
+            .map(|error| error.to_string())
+            .collect();
+
+        assert_eq!(
+            messages,
+            [
+                "The transition from q1 to q3 refers to q3, which is not a state.",
+                "The transition from q2 to q1 reads i9, which is not in the input alphabet.",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_invalid_definitions() {
+        let missing = definition(quote! { name: test, states: [q1] });
+        assert!(check(&missing)
+            .unwrap_err()
+            .to_string()
+            .contains("missing field"));
+
+        assert!(syn::parse2::<Definition>(quote! { states: [q1] }).is_err());
+        assert!(syn::parse2::<Definition>(quote! { name: test, states: [q1 q2] }).is_err());
+        assert!(syn::parse2::<Definition>(quote! { name: "1x" }).is_err());
+    }
+}
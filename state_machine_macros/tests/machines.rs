@@ -0,0 +1,71 @@
+use state_machine_macros::state_machine;
+
+state_machine! {
+    name: moore,
+    states: [q1, q2, q3],
+    input_alphabet: [i0, i1],
+    output_alphabet: [o5, o6],
+    transitions: [
+        { current_state: q1, read_symbol: [i0, i1], new_state: q2 },
+        { current_state: q2, read_symbol: [i1], new_state: q3 },
+    ],
+    output_function: [
+        { current_state: q1, output_symbol: [o5, o6] },
+    ],
+    start_state: q1,
+    end_states: [q3],
+}
+
+state_machine! {
+    name: mealy,
+    type: mealy,
+    states: [q1, q2, q3],
+    input_alphabet: [i0, i1],
+    output_alphabet: [o0, o1],
+    transitions: [
+        { current_state: q1, read_symbol: [i0, !i1], new_state: q2, output_symbol: o0 },
+        { current_state: q2, read_symbol: [i1], new_state: q3, output_symbol: o1 },
+        { current_state: q2, read_symbol: [!i0], new_state: q1, output_symbol: o0 },
+    ],
+    start_state: q1,
+    end_states: [q3],
+    variables: [
+        { name: counter, type: uint },
+        { name: gain, type: float, initial: 2.5 },
+    ],
+}
+
+#[test]
+fn test_moore_machine() {
+    let mut machine = moore::Machine::new();
+    let output = machine.step(moore::Input { i0: true, i1: true });
+
+    assert!(output.o5 && output.o6);
+    assert_eq!(machine.state(), moore::State::Q2);
+
+    machine.step(moore::Input {
+        i0: false,
+        i1: true,
+    });
+    assert!(machine.is_end_state());
+}
+
+#[test]
+fn test_mealy_machine() {
+    let mut machine = mealy::Machine::new();
+    assert_eq!(machine.counter, 0);
+    assert_eq!(machine.gain, 2.5);
+
+    let output = machine.step(mealy::Input {
+        i0: true,
+        i1: false,
+    });
+    assert!(output.o0 && !output.o1);
+
+    let output = machine.step(mealy::Input {
+        i0: false,
+        i1: true,
+    });
+    assert!(output.o1);
+    assert_eq!(machine.state(), mealy::State::Q3);
+}